@@ -128,27 +128,27 @@ public class MainActivity extends AppCompatActivity {
     @Override
     public void onSaveInstanceState(@NonNull Bundle outState) {
         wumpus.handle_event(td, "\"SaveInstanceState\"");
-      //  try {
+        try {
             String instance_state = wumpus.last_response_json(td);
             Log.d(TAG, "instance state " + instance_state);
-           /* JSONObject response = new JSONObject(instance_state);
+            JSONObject response = new JSONObject(instance_state);
             JSONArray tags = response.getJSONArray("tag_vec");
             ArrayList<String> keys = new ArrayList();
             for (int ix = 0; ix < tags.length(); ix++) {
                 JSONArray item = tags.getJSONArray(ix);
                 String key = item.getString(0);
-                if (key == "keys") Log.e(TAG, "cannot user 'keys' as a key");
+                if (key.equals("keys")) Log.e(TAG, "cannot user 'keys' as a key");
                 String value = item.getString(1);
                 Log.d(TAG, "state " + key + " = " + value);
                 outState.putString(key, value);
                 keys.add(key);
             }
             outState.putStringArrayList("keys", keys);
-            Log.d(TAG, "have instance state");*/
-      /*  } catch (JSONException e) {
+            Log.d(TAG, "have instance state");
+        } catch (JSONException e) {
             Log.e(TAG, "bad JSON for instance state");
             e.printStackTrace();
-        }*/
+        }
         super.onSaveInstanceState(outState);
     }
 
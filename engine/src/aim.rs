@@ -0,0 +1,73 @@
+/*! aiming: choosing the rooms to shoot an arrow through one at a time, so that only paths the arrow can fly along are chosen */
+
+use crate::{Cave, Data};
+use serde::Serialize;
+
+/// the most rooms an arrow can fly through
+pub const MAX_ARROW_PATH: usize = 5;
+
+/** An `AimView` is sent to the webview while the player is choosing the rooms to shoot an arrow through. */
+#[derive(Debug, Clone, Serialize)]
+pub struct AimView {
+    /// the rooms chosen so far
+    pub path: Vec<u8>,
+    /// the rooms the arrow can be aimed at next (none once the path is as long as it can be)
+    pub next: Vec<u8>,
+    /// a map showing the path, as SVG; clicking on a room that can be aimed at next calls `aimAt`
+    pub map: String,
+}
+/// the rooms that an arrow shot from `from` through the rooms of `path` can be aimed at next: those the tunnels from the last room lead to, but not the room before it, as arrows aren't that crooked
+pub fn next_rooms(cave: &Cave, from: u8, path: &[u8]) -> Vec<u8> {
+    if path.len() >= MAX_ARROW_PATH {
+        return vec![];
+    }
+    let last = path.last().cloned().unwrap_or(from);
+    let before = path.len().checked_sub(2).map(|k| path[k]);
+    let mut next: Vec<u8> = cave
+        .tunnels(last)
+        .iter()
+        .cloned()
+        .filter(|room| Some(*room) != before)
+        .collect();
+    next.sort_unstable();
+    next.dedup();
+    next
+}
+/// whether an arrow shot through the rooms of `path` would have to double back, which arrows can't
+pub fn is_crooked(path: &[u8]) -> bool {
+    (2..path.len()).any(|k| path[k - 2] == path[k])
+}
+impl Data {
+    /// check the rooms chosen so far to shoot an arrow through, keeping those the arrow can fly through, and show which rooms it can be aimed at next
+    pub(crate) fn aim(&mut self, mut path: Vec<u8>) -> AimView {
+        let from = self.l[0];
+        let wrong = (0..path.len())
+            .find(|k| !next_rooms(&self.cave, from, &path[..*k]).contains(&path[*k]));
+        if let Some(k) = wrong {
+            if k >= MAX_ARROW_PATH {
+                self.say(&format!(
+                    "AN ARROW CAN ONLY FLY THROUGH {} ROOMS",
+                    MAX_ARROW_PATH
+                ));
+            } else if is_crooked(&path[..=k]) {
+                self.say("ARROWS AREN'T THAT CROOKED - TRY ANOTHER ROOM");
+            } else {
+                let last = if k == 0 { from } else { path[k - 1] };
+                self.say(&format!("NO TUNNEL FROM {} TO {}", last, path[k]));
+            }
+            path.truncate(k);
+        }
+        let next = next_rooms(&self.cave, from, &path);
+        let knowledge = self.knowledge();
+        let mut map = self.draw(&knowledge);
+        map.aim(&path, &next);
+        AimView {
+            map: map.render(),
+            path,
+            next,
+        }
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,331 @@
+/*! the cave: the rooms and the tunnels between them */
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, f64::consts::PI};
+
+/// the names of the caves of Wumpus II, by number
+pub const WUMPUS_II_CAVES: [&str; 6] = [
+    "DODECAHEDRON",
+    "MOBIUS STRIP",
+    "STRING OF BEADS",
+    "HEX NETWORK",
+    "DENDRITE WITH DEGENERACIES",
+    "ONE WAY LATTICE",
+];
+/** A `Cave` lists, for each room, the rooms that its tunnels lead to. Rooms are numbered from 1. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Cave {
+    tunnels: Vec<Vec<u8>>,
+}
+impl Default for Cave {
+    fn default() -> Self {
+        Self::dodecahedron()
+    }
+}
+impl Cave {
+    /// the classic cave
+    pub fn dodecahedron() -> Self {
+        /* 0068 */
+        //  SET UP CAVE (DODECAHEDRAL NODE LIST)
+        /* 0130 */
+        Self::new(vec![
+            vec![2, 5, 8],
+            vec![1, 3, 10],
+            vec![2, 4, 12],
+            vec![3, 5, 14],
+            vec![1, 4, 6],
+            /* 0140 */
+            vec![5, 7, 15],
+            vec![6, 8, 17],
+            vec![1, 7, 9],
+            vec![8, 10, 18],
+            vec![2, 9, 11],
+            /* 0150 */
+            vec![10, 12, 19],
+            vec![3, 11, 13],
+            vec![12, 14, 20],
+            vec![4, 13, 15],
+            vec![6, 14, 16],
+            /* 0160 */
+            vec![15, 17, 20],
+            vec![7, 16, 18],
+            vec![9, 17, 19],
+            vec![11, 18, 20],
+            vec![13, 16, 19],
+        ])
+    }
+    /// one of the caves of Wumpus II, by number (see [WUMPUS_II_CAVES])
+    pub fn wumpus_ii(number: u8) -> Option<Self> {
+        match number {
+            0 => Some(Self::dodecahedron()),
+            1 => Some(Self::mobius_strip()),
+            2 => Some(Self::string_of_beads()),
+            3 => Some(Self::hex_network()),
+            4 => Some(Self::dendrite()),
+            5 => Some(Self::one_way_lattice()),
+            _ => None,
+        }
+    }
+    /// a ring of 20 rooms, each also joined to the room opposite
+    fn mobius_strip() -> Self {
+        Self::new(
+            (0..20)
+                .map(|i| vec![(i + 19) % 20 + 1, (i + 1) % 20 + 1, (i + 10) % 20 + 1])
+                .collect(),
+        )
+    }
+    /// five beads of four rooms each, strung in a ring
+    fn string_of_beads() -> Self {
+        Self::new(
+            (0..5u8)
+                .flat_map(|bead| {
+                    let first = 4 * bead + 1;
+                    let previous = (4 * bead + 19) % 20 + 1;
+                    let next = (4 * bead + 4) % 20 + 1;
+                    vec![
+                        vec![previous, first + 1, first + 2],
+                        vec![first, first + 2, first + 3],
+                        vec![first, first + 1, first + 3],
+                        vec![first + 1, first + 2, next],
+                    ]
+                })
+                .collect(),
+        )
+    }
+    /// a honeycomb of four rows of five rooms, wrapped round at the edges
+    fn hex_network() -> Self {
+        let room = |row: u8, column: u8| 5 * (row % 4) + column % 5 + 1;
+        Self::new(
+            (0..4u8)
+                .flat_map(|row| {
+                    (0..5u8).map(move |column| {
+                        let vertical = if (row + column) % 2 == 0 {
+                            room(row + 3, column)
+                        } else {
+                            room(row + 1, column)
+                        };
+                        vec![room(row, column + 4), room(row, column + 1), vertical]
+                    })
+                })
+                .collect(),
+        )
+    }
+    /// a tree of rooms; where a room has fewer than three neighbours, its spare tunnels lead to the same rooms again
+    fn dendrite() -> Self {
+        Self::new(
+            (1..=20u8)
+                .map(|room| {
+                    let mut tunnels: Vec<u8> = [room / 2, 2 * room, 2 * room + 1]
+                        .iter()
+                        .cloned()
+                        .filter(|to| *to >= 1 && *to <= 20)
+                        .collect();
+                    while tunnels.len() < 3 {
+                        tunnels.push(tunnels[0]);
+                    }
+                    tunnels
+                })
+                .collect(),
+        )
+    }
+    /// four rows of five rooms, wrapped round at the edges, where the tunnels only go right, down and diagonally
+    fn one_way_lattice() -> Self {
+        let room = |row: u8, column: u8| 5 * (row % 4) + column % 5 + 1;
+        Self::new(
+            (0..4u8)
+                .flat_map(|row| {
+                    (0..5u8).map(move |column| {
+                        vec![
+                            room(row, column + 1),
+                            room(row + 1, column),
+                            room(row + 1, column + 1),
+                        ]
+                    })
+                })
+                .collect(),
+        )
+    }
+    /// create a cave from the list of tunnels for each room
+    pub fn new(tunnels: Vec<Vec<u8>>) -> Self {
+        Self { tunnels }
+    }
+    /// check that the cave can be played in
+    pub fn check(&self) -> Result<()> {
+        if self.tunnels.len() < 6 || self.tunnels.len() > u8::MAX as usize {
+            return Err(anyhow!(
+                "a cave needs between 6 and {} rooms, not {}",
+                u8::MAX,
+                self.tunnels.len()
+            ));
+        }
+        for room in 1..=self.size() {
+            let tunnels = self.tunnels(room);
+            if tunnels.is_empty() {
+                return Err(anyhow!("room {} has no tunnels", room));
+            }
+            for to in tunnels {
+                if *to < 1 || *to > self.size() || *to == room {
+                    return Err(anyhow!("room {} has a bad tunnel to {}", room, to));
+                }
+            }
+        }
+        if let Some(room) = self.distances(1).iter().position(Option::is_none) {
+            return Err(anyhow!("room {} cannot be reached from room 1", room + 1));
+        }
+        for room in 2..=self.size() {
+            if self.distances(room)[0].is_none() {
+                return Err(anyhow!("room 1 cannot be reached from room {}", room));
+            }
+        }
+        Ok(())
+    }
+    /// add a room with no tunnels, returning its number
+    pub fn add_room(&mut self) -> u8 {
+        self.tunnels.push(vec![]);
+        self.size()
+    }
+    /// remove `room` and the tunnels to it; the rooms after it are numbered one less
+    pub fn remove_room(&mut self, room: u8) {
+        self.tunnels.remove(room as usize - 1);
+        for tunnels in &mut self.tunnels {
+            tunnels.retain(|to| *to != room);
+            for to in tunnels.iter_mut().filter(|to| **to > room) {
+                *to -= 1;
+            }
+        }
+    }
+    /// add a tunnel from `from` to `to`, if there is not one already
+    pub fn add_tunnel(&mut self, from: u8, to: u8) {
+        if !self.is_tunnel(from, to) {
+            self.tunnels[from as usize - 1].push(to);
+        }
+    }
+    /// remove the tunnel from `from` to `to`, if there is one
+    pub fn remove_tunnel(&mut self, from: u8, to: u8) {
+        self.tunnels[from as usize - 1].retain(|room| *room != to);
+    }
+    /// the number of rooms
+    pub fn size(&self) -> u8 {
+        self.tunnels.len() as u8
+    }
+    /// the rooms that the tunnels from `room` lead to
+    pub fn tunnels(&self, room: u8) -> &[u8] {
+        &self.tunnels[room as usize - 1]
+    }
+    /// whether there is a tunnel from `from` to `to`
+    pub fn is_tunnel(&self, from: u8, to: u8) -> bool {
+        self.tunnels(from).contains(&to)
+    }
+    /// the number of tunnels to go through to get from `from` to each room (indexed from 0), if it can be reached at all
+    pub fn distances(&self, from: u8) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.tunnels.len()];
+        distances[from as usize - 1] = Some(0);
+        let mut queue = VecDeque::new();
+        queue.push_back(from);
+        while let Some(room) = queue.pop_front() {
+            let next = distances[room as usize - 1].map(|d| d + 1);
+            for to in self.tunnels(room) {
+                if distances[*to as usize - 1].is_none() {
+                    distances[*to as usize - 1] = next;
+                    queue.push_back(*to);
+                }
+            }
+        }
+        distances
+    }
+    /// whether this is the classic cave
+    pub fn is_dodecahedron(&self) -> bool {
+        *self == Self::dodecahedron()
+    }
+    /// positions of the rooms for drawing a map, each coordinate between 0 and 100
+    pub fn layout(&self) -> Vec<(f64, f64)> {
+        if self.is_dodecahedron() {
+            schlegel_layout()
+        } else {
+            self.force_directed_layout()
+        }
+    }
+    /// lay out the rooms by treating the tunnels as springs and the rooms as repelling each other
+    fn force_directed_layout(&self) -> Vec<(f64, f64)> {
+        let n = self.tunnels.len();
+        let k = (1.0 / n as f64).sqrt();
+        let mut pos: Vec<(f64, f64)> = (0..n)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / n as f64;
+                (0.5 + 0.4 * angle.cos(), 0.5 + 0.4 * angle.sin())
+            })
+            .collect();
+        let mut temperature = 0.1;
+        for _ in 0..300 {
+            let mut disp = vec![(0.0, 0.0); n];
+            for i in 0..n {
+                for j in 0..n {
+                    if i != j {
+                        let (dx, dy) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1);
+                        let d = (dx * dx + dy * dy).sqrt().max(0.001);
+                        let f = k * k / d;
+                        disp[i].0 += dx / d * f;
+                        disp[i].1 += dy / d * f;
+                    }
+                }
+            }
+            for (i, tunnels) in self.tunnels.iter().enumerate() {
+                for to in tunnels {
+                    let j = *to as usize - 1;
+                    let (dx, dy) = (pos[i].0 - pos[j].0, pos[i].1 - pos[j].1);
+                    let d = (dx * dx + dy * dy).sqrt().max(0.001);
+                    let f = d * d / k;
+                    disp[i].0 -= dx / d * f;
+                    disp[i].1 -= dy / d * f;
+                    disp[j].0 += dx / d * f;
+                    disp[j].1 += dy / d * f;
+                }
+            }
+            for i in 0..n {
+                let d = (disp[i].0 * disp[i].0 + disp[i].1 * disp[i].1)
+                    .sqrt()
+                    .max(0.001);
+                let step = d.min(temperature);
+                pos[i].0 += disp[i].0 / d * step;
+                pos[i].1 += disp[i].1 / d * step;
+            }
+            temperature *= 0.98;
+        }
+        fit(pos)
+    }
+}
+/// the classic cave drawn as a Schlegel diagram: an outer pentagon, a ring of ten rooms and an inner pentagon
+fn schlegel_layout() -> Vec<(f64, f64)> {
+    let mut pos = vec![(0.0, 0.0); 20];
+    let mut place = |room: usize, radius: f64, degrees: f64| {
+        let angle = degrees * PI / 180.0;
+        pos[room - 1] = (50.0 + radius * angle.cos(), 50.0 + radius * angle.sin());
+    };
+    for (i, room) in [1, 2, 3, 4, 5].iter().enumerate() {
+        place(*room, 45.0, -90.0 + 72.0 * i as f64);
+    }
+    for (i, room) in [8, 9, 10, 11, 12, 13, 14, 15, 6, 7].iter().enumerate() {
+        let radius = if i % 2 == 0 { 32.0 } else { 25.0 };
+        place(*room, radius, -90.0 + 36.0 * i as f64);
+    }
+    for (i, room) in [18, 19, 20, 16, 17].iter().enumerate() {
+        place(*room, 13.0, -54.0 + 72.0 * i as f64);
+    }
+    pos
+}
+/// scale positions to fit in the map, leaving room for the circles at the edges
+fn fit(pos: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    let min_x = pos.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let max_x = pos.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let min_y = pos.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_y = pos.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let scale = 90.0 / (max_x - min_x).max(max_y - min_y).max(0.001);
+    pos.iter()
+        .map(|(x, y)| (5.0 + (x - min_x) * scale, 5.0 + (y - min_y) * scale))
+        .collect()
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
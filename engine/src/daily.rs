@@ -0,0 +1,103 @@
+/*! the daily challenge: the same game for everyone who plays on the same day */
+
+use crate::cave::WUMPUS_II_CAVES;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/** A `Daily` challenge is the game for one day. The date chooses the cave (in Wumpus II) and the seed for the dice, so everyone who plays that day with the same settings gets the same game. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Daily {
+    /// the day, as YYYY-MM-DD (in UTC)
+    pub date: String,
+    /// the seed for the dice
+    pub seed: u64,
+}
+impl Daily {
+    /// today's challenge
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or_default();
+        Self::on_day(seconds / 86400)
+    }
+    /// the challenge for the day that is `days` days after 1970-01-01
+    pub fn on_day(days: u64) -> Self {
+        // the days since 0000-03-01, so that leap days come at the end of the year
+        let days = days + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+        Self {
+            date: format!("{:04}-{:02}-{:02}", year, month, day),
+            seed: year * 10000 + month * 100 + day,
+        }
+    }
+    /// the number of the cave of Wumpus II to play in (see [WUMPUS_II_CAVES])
+    pub fn cave(&self) -> u8 {
+        (self.seed % WUMPUS_II_CAVES.len() as u64) as u8
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_have_the_right_dates() {
+        for (days, date) in [
+            (0, "1970-01-01"),
+            (11016, "2000-02-29"),
+            (11017, "2000-03-01"),
+            (20745, "2026-10-19"),
+            (47846, "2100-12-31"),
+        ] {
+            assert_eq!(Daily::on_day(days).date, date);
+        }
+    }
+    #[test]
+    fn seed_is_the_same_all_day_and_differs_each_day() {
+        assert_eq!(Daily::on_day(20745).seed, 20261019);
+        assert_eq!(Daily::on_day(20745), Daily::on_day(20745));
+        let seeds: Vec<u64> = (20000..20400)
+            .map(|days| Daily::on_day(days).seed)
+            .collect();
+        let mut distinct = seeds.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert_eq!(distinct.len(), seeds.len());
+    }
+    #[test]
+    fn everyone_gets_the_same_game_on_the_same_day() {
+        let game = |days| {
+            let mut data = crate::Data::new(crate::Cave::dodecahedron(), &crate::Config::default());
+            data.start_daily(Daily::on_day(days));
+            let hazards: Vec<Vec<u8>> = data
+                .hazards
+                .iter()
+                .map(|placed| placed.rooms.clone())
+                .collect();
+            (data.l.clone(), hazards)
+        };
+        assert_eq!(game(20745), game(20745));
+        assert_ne!(game(20745), game(20746));
+    }
+    #[test]
+    fn cave_is_one_of_wumpus_ii() {
+        for days in 20000..20100 {
+            assert!((Daily::on_day(days).cave() as usize) < WUMPUS_II_CAVES.len());
+        }
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
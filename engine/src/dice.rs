@@ -0,0 +1,39 @@
+/*! the dice: all the random choices made during a game */
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/** `Dice` make the random choices for a game. Seeded dice make the same choices every time, so that a game can be repeated. */
+#[derive(Debug, Clone)]
+pub struct Dice {
+    rng: StdRng,
+}
+impl Default for Dice {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::from_entropy(),
+        }
+    }
+}
+impl Dice {
+    /// create dice, seeded if a seed is given
+    pub fn new(seed: Option<u64>) -> Self {
+        match seed {
+            Some(seed) => Self {
+                rng: StdRng::seed_from_u64(seed),
+            },
+            None => Self::default(),
+        }
+    }
+    /* 0170 */
+    /// a number from 1 to `n`
+    pub fn upto(&mut self, n: usize) -> usize {
+        (n as f64 * self.rng.gen::<f64>()) as usize + 1
+    }
+    /// the underlying random number generator
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,417 @@
+/*! the cave editor: designing a cave, with where the player starts and where the wumpuses and hazards are, and playtesting it */
+
+use crate::{map::Map, scenario::Scenario, Action, Cave, Config, Data, Engine, Response};
+use anyhow::{anyhow, Result};
+use narthex_engine_trait::EngineTrait;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/** A `CaveFile` is a cave designed in the editor, with where everything is in it, as it is saved. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CaveFile {
+    /// for each room, the rooms its tunnels lead to, as for the `cave` of the [Config]
+    pub cave: Cave,
+    /// the room the player starts in
+    pub start: u8,
+    #[serde(default)]
+    pub wumpuses: Vec<u8>,
+    #[serde(default)]
+    pub pits: Vec<u8>,
+    #[serde(default)]
+    pub bats: Vec<u8>,
+}
+impl Default for CaveFile {
+    fn default() -> Self {
+        Self::new(Cave::default())
+    }
+}
+/** A `Placement` is what can be put in a room of a [CaveFile]. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Placement {
+    /// nothing: clear the room
+    #[default]
+    Nothing,
+    Wumpus,
+    Pit,
+    Bats,
+}
+impl CaveFile {
+    /// a design for `cave`, with the player starting in room 1 and nothing else placed yet
+    pub fn new(cave: Cave) -> Self {
+        Self {
+            cave,
+            start: 1,
+            wumpuses: vec![],
+            pits: vec![],
+            bats: vec![],
+        }
+    }
+    /// check that the cave can be played in
+    pub fn check(&self) -> Result<()> {
+        self.cave.check()?;
+        let mut taken = vec![self.start];
+        for room in self.wumpuses.iter().chain(&self.pits).chain(&self.bats) {
+            if taken.contains(room) {
+                return Err(anyhow!("room {} is used twice", room));
+            }
+            taken.push(*room);
+        }
+        if let Some(room) = taken.iter().find(|room| !self.exists(**room)) {
+            return Err(anyhow!("there is no room {}", room));
+        }
+        if self.wumpuses.is_empty() {
+            return Err(anyhow!("there must be at least one wumpus"));
+        }
+        Ok(())
+    }
+    /// whether there is a room numbered `room`
+    fn exists(&self, room: u8) -> bool {
+        room >= 1 && room <= self.cave.size()
+    }
+    /// the rooms with each [Placement] but [Placement::Nothing]
+    fn rooms_mut(&mut self) -> [&mut Vec<u8>; 3] {
+        [&mut self.wumpuses, &mut self.pits, &mut self.bats]
+    }
+    /// put `placement` in `room`, instead of whatever was there
+    fn place(&mut self, room: u8, placement: Placement) {
+        for rooms in &mut self.rooms_mut() {
+            rooms.retain(|placed| *placed != room);
+        }
+        match placement {
+            Placement::Nothing => {}
+            Placement::Wumpus => self.wumpuses.push(room),
+            Placement::Pit => self.pits.push(room),
+            Placement::Bats => self.bats.push(room),
+        }
+    }
+    /// remove `room`, and whatever is in it; the rooms after it are numbered one less
+    fn remove_room(&mut self, room: u8) {
+        self.cave.remove_room(room);
+        let renumber = |placed: u8| if placed > room { placed - 1 } else { placed };
+        for rooms in &mut self.rooms_mut() {
+            rooms.retain(|placed| *placed != room);
+            for placed in rooms.iter_mut() {
+                *placed = renumber(*placed);
+            }
+        }
+        self.start = if self.start == room {
+            1
+        } else {
+            renumber(self.start)
+        };
+    }
+    /// read a design from JSON, which need not be finished yet, but must only mention rooms that are in the cave
+    pub fn from_json(json: &str) -> Result<Self> {
+        let design: Self = serde_json::from_str(json)?;
+        if !design.exists(design.start) {
+            return Err(anyhow!("there is no room {} to start in", design.start));
+        }
+        for from in 1..=design.cave.size() {
+            if let Some(to) = design
+                .cave
+                .tunnels(from)
+                .iter()
+                .find(|to| !design.exists(**to))
+            {
+                return Err(anyhow!(
+                    "the tunnel from room {} leads to no room {}",
+                    from,
+                    to
+                ));
+            }
+        }
+        let placed = design
+            .wumpuses
+            .iter()
+            .chain(&design.pits)
+            .chain(&design.bats);
+        if let Some(room) = placed.clone().find(|room| !design.exists(**room)) {
+            return Err(anyhow!("there is no room {}", room));
+        }
+        Ok(design)
+    }
+    /// the design as JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+    /// read a design from a JSON file, if there is one
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::from_json(&fs::read_to_string(path)?)
+            .map(Some)
+            .map_err(|err| anyhow!("cave file {}: {}", path.display(), err))
+    }
+    /// write the design to a JSON file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+    /// set up a game in the cave, to playtest it
+    pub(crate) fn data(&self) -> Result<Data> {
+        self.check()?;
+        let config = Config {
+            pits: self.pits.len(),
+            bats: self.bats.len(),
+            wumpuses: self.wumpuses.len(),
+            ..Config::default()
+        };
+        let mut data = Data::new(self.cave.clone(), &config);
+        data.place(self.start, &self.wumpuses, &[&self.pits, &self.bats], &[]);
+        data.msgs = vec!["PLAYTESTING YOUR CAVE".to_string()];
+        Ok(data)
+    }
+    /// draw the cave with where everything is, for the designer to click on
+    fn map(&self) -> String {
+        let mut map = Map::new(&self.cave, Some(self.start));
+        map.label(self.start, "START");
+        for (rooms, label, class) in [
+            (&self.wumpuses, "WUMPUS", "wumpus"),
+            (&self.pits, "PIT", "pit"),
+            (&self.bats, "BATS", "bats"),
+        ] {
+            for room in rooms {
+                map.label(*room, label);
+                map.mark(*room, class);
+            }
+        }
+        map.selectable("editRoom");
+        map.render()
+    }
+}
+/** The `Editor` keeps the cave being designed, and whether it is being edited. */
+#[derive(Debug, Default)]
+pub struct Editor {
+    design: CaveFile,
+    /// whether the editor is open
+    editing: bool,
+    /// the file to save the design in, if it is kept in a file
+    file: Option<PathBuf>,
+}
+impl Editor {
+    /// an editor for the design in `file`, if there is one, or else for `cave`
+    pub fn new(cave: &Cave, file: Option<PathBuf>) -> Result<Self> {
+        let design = match &file {
+            Some(path) => CaveFile::load(path)?,
+            None => None,
+        };
+        Ok(Self {
+            design: design.unwrap_or_else(|| CaveFile::new(cave.clone())),
+            editing: false,
+            file,
+        })
+    }
+    /// whether the editor is open
+    pub fn editing(&self) -> bool {
+        self.editing
+    }
+    /// the cave being designed
+    pub fn design(&self) -> &CaveFile {
+        &self.design
+    }
+    /// carry on with a design kept when the app was stopped
+    pub fn restore(&mut self, json: &str) -> Result<()> {
+        self.design = CaveFile::from_json(json)?;
+        Ok(())
+    }
+    /// what to show in the editor
+    fn view(&self) -> Result<EditorView> {
+        Ok(EditorView {
+            design: self.design.clone(),
+            map: self.design.map(),
+            problem: self.design.check().err().map(|err| err.to_string()),
+            json: self.design.to_json()?,
+        })
+    }
+    /// change the design as `action` asks, returning what to tell the designer
+    fn change(&mut self, action: Action) -> Result<String> {
+        let design = &mut self.design;
+        let rooms = match action {
+            Action::RemoveRoom(room) | Action::PlaceInRoom(room, _) | Action::SetStart(room) => {
+                vec![room]
+            }
+            Action::AddTunnel { from, to, .. } | Action::RemoveTunnel { from, to, .. } => {
+                vec![from, to]
+            }
+            _ => vec![],
+        };
+        let missing = rooms.into_iter().find(|room| !design.exists(*room));
+        if let Some(room) = missing {
+            return Ok(format!("THERE IS NO ROOM {}", room));
+        }
+        Ok(match action {
+            Action::AddRoom if design.cave.size() == u8::MAX => {
+                format!("A CAVE CAN HAVE AT MOST {} ROOMS", u8::MAX)
+            }
+            Action::AddRoom => format!("ADDED ROOM {}", design.cave.add_room()),
+            Action::RemoveRoom(_) if design.cave.size() == 1 => {
+                "A CAVE MUST HAVE AT LEAST ONE ROOM".to_string()
+            }
+            Action::RemoveRoom(room) => {
+                design.remove_room(room);
+                format!(
+                    "REMOVED ROOM {}; THE ROOMS AFTER IT ARE NUMBERED ONE LESS",
+                    room
+                )
+            }
+            Action::AddTunnel { from, to, .. } | Action::RemoveTunnel { from, to, .. }
+                if from == to =>
+            {
+                "A TUNNEL CANNOT LEAD BACK TO ITS OWN ROOM".to_string()
+            }
+            Action::AddTunnel {
+                from,
+                to,
+                both_ways,
+            } => {
+                design.cave.add_tunnel(from, to);
+                if both_ways {
+                    design.cave.add_tunnel(to, from);
+                }
+                format!(
+                    "TUNNEL FROM {} TO {}{}",
+                    from,
+                    to,
+                    if both_ways { " AND BACK" } else { "" }
+                )
+            }
+            Action::RemoveTunnel {
+                from,
+                to,
+                both_ways,
+            } => {
+                design.cave.remove_tunnel(from, to);
+                if both_ways {
+                    design.cave.remove_tunnel(to, from);
+                }
+                format!(
+                    "NO TUNNEL FROM {} TO {}{}",
+                    from,
+                    to,
+                    if both_ways { " OR BACK" } else { "" }
+                )
+            }
+            Action::PlaceInRoom(room, placement) if room == design.start => {
+                if placement == Placement::Nothing {
+                    String::new()
+                } else {
+                    format!("THE PLAYER STARTS IN ROOM {}", room)
+                }
+            }
+            Action::PlaceInRoom(room, placement) => {
+                design.place(room, placement);
+                String::new()
+            }
+            Action::SetStart(room) => {
+                design.place(room, Placement::Nothing);
+                design.start = room;
+                format!("THE PLAYER STARTS IN ROOM {}", room)
+            }
+            Action::SaveCave => match (design.check(), &self.file) {
+                (Err(err), _) => unplayable(err),
+                (Ok(()), Some(path)) => {
+                    design.save(path)?;
+                    format!("SAVED THE CAVE IN {}", path.display())
+                }
+                (Ok(()), None) => {
+                    "THE CAVE CAN BE PLAYED. COPY THE CAVE FILE BELOW TO KEEP IT".to_string()
+                }
+            },
+            _ => String::new(),
+        })
+    }
+}
+/** An `EditorView` is sent to the webview while the cave editor is open. */
+#[derive(Debug, Clone, Serialize)]
+pub struct EditorView {
+    design: CaveFile,
+    /// a map of the cave with where everything is, as SVG; clicking on a room calls `editRoom`
+    map: String,
+    /// why the cave cannot be played yet, if it cannot
+    problem: Option<String>,
+    /// the design in the format of a cave file
+    json: String,
+}
+impl Engine {
+    /// carry out `action` in the cave editor, opening it if need be, or close the editor if the action is not for it
+    pub(crate) fn edit(&mut self, action: Action) -> Result<Response> {
+        let msg = match action {
+            Action::Playtest => match self.editor.design.check() {
+                Ok(()) => {
+                    self.editor.editing = false;
+                    return self.start_scenario(Scenario::Playtest, vec![]);
+                }
+                Err(err) => unplayable(err),
+            },
+            Action::StopEditing => {
+                self.editor.editing = false;
+                self.data.msgs.clear();
+                return Ok(self.data.create_response());
+            }
+            Action::EditCave => "CAVE EDITOR: CLICK ON THE ROOMS TO CHANGE THEM".to_string(),
+            action if action.edits() => self.editor.change(action)?,
+            action => {
+                self.editor.editing = false;
+                return self.execute(action);
+            }
+        };
+        self.leave_scenario();
+        self.editor.editing = true;
+        Ok(Response {
+            msgs: msg,
+            editor: Some(self.editor.view()?),
+            ..Response::default()
+        })
+    }
+}
+/// what to tell the designer when the cave cannot be played
+fn unplayable(err: anyhow::Error) -> String {
+    format!(
+        "THE CAVE CANNOT BE PLAYED YET: {}",
+        err.to_string().to_uppercase()
+    )
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn good_design_is_read() -> Result<()> {
+        let design = CaveFile::from_json(r#"{"cave": [[2], [1]], "start": 2, "wumpuses": [1]}"#)?;
+        assert_eq!(design.start, 2);
+        assert_eq!(CaveFile::from_json(&design.to_json()?)?, design);
+        Ok(())
+    }
+    #[test]
+    fn bad_start_room_is_refused() {
+        assert!(CaveFile::from_json(r#"{"cave": [[2], [1]], "start": 9}"#).is_err());
+        assert!(CaveFile::from_json(r#"{"cave": [[2], [1]], "start": 0}"#).is_err());
+    }
+    #[test]
+    fn bad_tunnel_is_refused() {
+        assert!(CaveFile::from_json(r#"{"cave": [[2], [1, 7]], "start": 1}"#).is_err());
+        assert!(CaveFile::from_json(r#"{"cave": [[0], [1]], "start": 1}"#).is_err());
+    }
+    #[test]
+    fn bad_placement_is_refused() {
+        assert!(CaveFile::from_json(r#"{"cave": [[2], [1]], "start": 1, "pits": [3]}"#).is_err());
+    }
+    #[test]
+    fn bad_design_is_not_restored() -> Result<()> {
+        let mut editor = Editor::new(&Cave::dodecahedron(), None)?;
+        assert!(editor
+            .restore(r#"{"cave": [[2], [1]], "start": 9}"#)
+            .is_err());
+        assert_eq!(editor.design().cave, Cave::dodecahedron());
+        assert!(editor.view().is_ok());
+        Ok(())
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
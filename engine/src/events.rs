@@ -0,0 +1,152 @@
+/*! events: what happens in the game as it is played, for observers such as front ends, statistics and analytics */
+
+use crate::Cause;
+use serde::Serialize;
+use std::fmt;
+
+/** A `GameEvent` is something that happened in the game. */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum GameEvent {
+    /// a new game started, with the player in `room`
+    Started { room: u8 },
+    /// the player went through a tunnel
+    Moved { from: u8, to: u8 },
+    /// the player walked into a wumpus's room
+    Bumped { room: u8 },
+    /// bats carried the player off
+    Snatched { from: u8, to: u8 },
+    /// the player picked up arrows lying in `room`, or found a quiver there
+    PickedUp { room: u8, arrows: i8 },
+    /// an arrow flew from one room into the next; it was `diverted` if there was no tunnel to the room it was shot at, so it went another way
+    ArrowFlew { from: u8, to: u8, diverted: bool },
+    /// an arrow killed the wumpus in `room`
+    WumpusKilled { room: u8 },
+    /// an arrow hit another player, in a hot-seat game
+    PlayerShot { player: usize },
+    /// a wumpus in `room` woke up at a noise (it may stay where it is)
+    WumpusWoke { room: u8 },
+    /// a wumpus moved
+    WumpusMoved { from: u8, to: u8 },
+    /// it is now `player`'s turn, in a hot-seat game
+    TurnChanged { player: usize },
+    /// the player won
+    Won,
+    /// the player lost, or (in a hot-seat game) went out
+    Lost { player: usize, cause: Cause },
+}
+impl GameEvent {
+    /// the rooms the arrow shot went through among `events`, from the room it was shot from, or nothing if no arrow was shot
+    pub fn arrow_path(events: &[Self]) -> Vec<u8> {
+        path(events.iter().filter_map(|event| match event {
+            Self::ArrowFlew { from, to, .. } => Some((*from, *to)),
+            _ => None,
+        }))
+    }
+    /// the rooms bats carried the player between among `events`, from the room with the bats, or nothing if they did not
+    pub fn bat_path(events: &[Self]) -> Vec<u8> {
+        path(events.iter().filter_map(|event| match event {
+            Self::Snatched { from, to } => Some((*from, *to)),
+            _ => None,
+        }))
+    }
+}
+/// the rooms that a journey of `legs`, each starting where the one before ended, went through
+fn path(legs: impl Iterator<Item = (u8, u8)>) -> Vec<u8> {
+    let mut rooms = vec![];
+    for (from, to) in legs {
+        if rooms.is_empty() {
+            rooms.push(from);
+        }
+        rooms.push(to);
+    }
+    rooms
+}
+/** An `Observer` is told about every [GameEvent], in the order they happen, once the [Engine](crate::Engine) has carried out the action that caused them. */
+pub trait Observer: Send {
+    fn notify(&mut self, event: &GameEvent);
+}
+/** The `Observers` are the [Observer]s registered on the [Engine](crate::Engine). */
+#[derive(Default)]
+pub struct Observers(Vec<Box<dyn Observer>>);
+impl Observers {
+    /// add an observer
+    pub fn add(&mut self, observer: Box<dyn Observer>) {
+        self.0.push(observer);
+    }
+    /// tell every observer about each event, in order
+    pub fn notify(&mut self, events: &[GameEvent]) {
+        for event in events {
+            for observer in &mut self.0 {
+                observer.notify(event);
+            }
+        }
+    }
+}
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} observers", self.0.len())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tests::game, Action};
+    use anyhow::Result;
+    use narthex_engine_trait::EngineTrait;
+    use std::sync::{Arc, Mutex};
+
+    /** A `Recorder` keeps every event it is told about. */
+    struct Recorder(Arc<Mutex<Vec<GameEvent>>>);
+    impl Observer for Recorder {
+        fn notify(&mut self, event: &GameEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+    #[test]
+    fn observers_get_events_in_order() -> Result<()> {
+        let mut engine = game(r#"{"pits": 0, "bats": 0, "seed": 5}"#)?;
+        let first = Arc::new(Mutex::new(vec![]));
+        let second = Arc::new(Mutex::new(vec![]));
+        engine.observe(Box::new(Recorder(first.clone())));
+        engine.observe(Box::new(Recorder(second.clone())));
+        let start = engine.data.l[0];
+        let to = engine.data.cave.tunnels(start)[0];
+        engine.execute(Action::Move(to))?;
+        let events = first.lock().unwrap().clone();
+        assert_eq!(events[0], GameEvent::Started { room: start });
+        assert_eq!(events[1], GameEvent::Moved { from: start, to });
+        assert_eq!(*second.lock().unwrap(), events);
+
+        // each action's events come after those of the one before
+        let back = engine.data.l[0];
+        engine.execute(Action::Move(engine.data.cave.tunnels(back)[0]))?;
+        let more = first.lock().unwrap().clone();
+        assert_eq!(more[..events.len()], events[..]);
+        assert!(matches!(more[events.len()], GameEvent::Moved { from, .. } if from == back));
+        Ok(())
+    }
+    #[test]
+    fn paths_are_followed_from_leg_to_leg() {
+        let events = [
+            GameEvent::Moved { from: 1, to: 2 },
+            GameEvent::ArrowFlew {
+                from: 2,
+                to: 3,
+                diverted: false,
+            },
+            GameEvent::WumpusWoke { room: 9 },
+            GameEvent::ArrowFlew {
+                from: 3,
+                to: 4,
+                diverted: true,
+            },
+            GameEvent::Snatched { from: 4, to: 17 },
+        ];
+        assert_eq!(GameEvent::arrow_path(&events), [2, 3, 4]);
+        assert_eq!(GameEvent::bat_path(&events), [4, 17]);
+        assert!(GameEvent::arrow_path(&events[..1]).is_empty());
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
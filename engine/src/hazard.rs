@@ -0,0 +1,176 @@
+/*! hazards: things in the rooms of the cave, other than the wumpus, that can happen to the player */
+
+use crate::{dice::Dice, Cause, Cave};
+use std::fmt;
+
+/** A `Sense` is which of the player's [crate::Senses] a hazard shows up in, so that what the player knows and the hints can take it into account. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sense {
+    Draft,
+    Bats,
+}
+/** An `Effect` is what happens when the player goes into a room with a hazard. */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Effect {
+    /// nothing happens
+    Nothing,
+    /// the player loses
+    Lose { cause: Cause, msg: &'static str },
+    /// the player is taken to another room
+    Carry { to: u8, msg: &'static str },
+}
+/** A `Hazard` is a kind of hazard, which may be in several rooms. */
+pub trait Hazard: fmt::Debug + Send {
+    /// the number of rooms with this hazard
+    fn count(&self) -> usize;
+    /// the label shown on the map of a room with this hazard
+    fn label(&self) -> &'static str;
+    /// the CSS class for a room with this hazard
+    fn class(&self) -> &'static str;
+    /// what the player is told when next to a room with this hazard
+    fn warning(&self) -> &'static str;
+    /// which of the player's senses notices this hazard, if any
+    fn sense(&self) -> Option<Sense> {
+        None
+    }
+    /// choose the rooms for this hazard, avoiding rooms already `taken`
+    fn place(&mut self, cave: &Cave, taken: &[u8], dice: &mut Dice) -> Vec<u8> {
+        let mut rooms = vec![];
+        while rooms.len() < self.count() {
+            let room = dice.upto(cave.size() as usize) as u8;
+            /* 0280 */
+            // CHECK FOR CROSSOVERS (IE L(1)=L(2),ETC)
+            if !taken.contains(&room) && !rooms.contains(&room) {
+                rooms.push(room);
+            }
+        }
+        rooms
+    }
+    /// what happens to the player on going into a room with this hazard
+    fn enter(&mut self, cave: &Cave, room: u8, dice: &mut Dice) -> Effect;
+    /// what happens to an arrow going through a room with this hazard: the message to show if it stops the arrow
+    fn arrow_passes(&mut self, _room: u8, _dice: &mut Dice) -> Option<&'static str> {
+        None
+    }
+}
+/** A `Placed` hazard is a [Hazard] and the rooms it is in. */
+#[derive(Debug)]
+pub struct Placed {
+    pub hazard: Box<dyn Hazard>,
+    pub rooms: Vec<u8>,
+}
+/// the hazards of the classic game
+pub fn classic(pits: usize, bats: usize) -> Vec<Placed> {
+    let hazards: Vec<Box<dyn Hazard>> = vec![Box::new(Pits(pits)), Box::new(Bats(bats))];
+    hazards
+        .into_iter()
+        .map(|hazard| Placed {
+            hazard,
+            rooms: vec![],
+        })
+        .collect()
+}
+/** `Pits` are bottomless: the player falls in and loses. */
+#[derive(Debug)]
+struct Pits(usize);
+impl Hazard for Pits {
+    fn count(&self) -> usize {
+        self.0
+    }
+    fn label(&self) -> &'static str {
+        "PIT"
+    }
+    fn class(&self) -> &'static str {
+        "pit"
+    }
+    /* 2080 */
+    fn warning(&self) -> &'static str {
+        "I FEEL A DRAFT"
+    }
+    fn sense(&self) -> Option<Sense> {
+        Some(Sense::Draft)
+    }
+    /* 4220 */
+    fn enter(&mut self, _cave: &Cave, _room: u8, _dice: &mut Dice) -> Effect {
+        Effect::Lose {
+            cause: Cause::Pit,
+            msg: "YYYIIIIEEEE . . . FELL IN PIT",
+        }
+    }
+}
+/** Super `Bats` grab the player and take them to some other room at random. */
+#[derive(Debug)]
+struct Bats(usize);
+impl Hazard for Bats {
+    fn count(&self) -> usize {
+        self.0
+    }
+    fn label(&self) -> &'static str {
+        "BATS"
+    }
+    fn class(&self) -> &'static str {
+        "bats"
+    }
+    /* 2100 */
+    fn warning(&self) -> &'static str {
+        "BATS NEARBY!"
+    }
+    fn sense(&self) -> Option<Sense> {
+        Some(Sense::Bats)
+    }
+    /* 4270 */
+    fn enter(&mut self, cave: &Cave, _room: u8, dice: &mut Dice) -> Effect {
+        /* 4290 */
+        Effect::Carry {
+            to: dice.upto(cave.size() as usize) as u8,
+            msg: "ZAP--SUPER BAT SNATCH! ELSEWHEREVILLE FOR YOU!",
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hazards_never_go_in_taken_rooms() {
+        let cave = Cave::dodecahedron();
+        let taken = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        for seed in 0..50 {
+            let mut dice = Dice::new(Some(seed));
+            let mut placed = taken.to_vec();
+            for mut hazard in classic(4, 4).into_iter().map(|placed| placed.hazard) {
+                let rooms = hazard.place(&cave, &placed, &mut dice);
+                assert_eq!(rooms.len(), 4);
+                for room in &rooms {
+                    assert!(!placed.contains(room), "room {} was taken", room);
+                    placed.push(*room);
+                }
+            }
+            // every free room is now used, once
+            placed.sort_unstable();
+            assert_eq!(placed, (1..=20).collect::<Vec<u8>>());
+        }
+    }
+    #[test]
+    fn pits_lose_and_bats_carry() {
+        let cave = Cave::dodecahedron();
+        let mut dice = Dice::new(Some(1));
+        let mut hazards = classic(1, 1);
+        assert!(matches!(
+            hazards[0].hazard.enter(&cave, 1, &mut dice),
+            Effect::Lose {
+                cause: Cause::Pit,
+                ..
+            }
+        ));
+        for _ in 0..100 {
+            match hazards[1].hazard.enter(&cave, 1, &mut dice) {
+                Effect::Carry { to, .. } => assert!((1..=20).contains(&to)),
+                effect => panic!("bats did not carry the player: {:?}", effect),
+            }
+        }
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,459 @@
+/*! hints: the chance of each room holding each hazard, given what the player has sensed */
+
+use crate::{
+    dice::Dice,
+    knowledge::{Knowledge, Observation, Senses},
+    Cave,
+};
+use log::debug;
+use rand::seq::SliceRandom;
+use serde::Serialize;
+
+/// the most placements of one kind of hazard for which the chances are worked out exactly
+const EXACT_LIMIT: u64 = 5000;
+/// how many consistent placements of each hazard to find when sampling
+const SAMPLES: usize = 2000;
+/// how many random placements to try for each hazard when sampling
+const TRIES: usize = 50000;
+/// the chance of hitting the wumpus that is worth an arrow
+const SHOOT_CHANCE: f64 = 0.5;
+
+/** `Chances` are the probabilities that a room holds each hazard. */
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct Chances {
+    pub wumpus: f64,
+    pub pit: f64,
+    pub bats: f64,
+}
+impl Chances {
+    /// the chance of dying on entering the room
+    fn danger(&self) -> f64 {
+        (self.wumpus + self.pit).min(1.0)
+    }
+}
+/** `Advice` is what the hint suggests doing next. */
+#[derive(Debug, Clone, Serialize)]
+pub enum Advice {
+    /// move to the room with the least chance of death
+    Move { room: u8, danger: f64 },
+    /// shoot along the path most likely to hit the wumpus
+    Shoot { path: Vec<u8>, chance: f64 },
+    /// the observations cannot be explained (this should not happen)
+    Confused,
+}
+/** A `Hint` is advice worked out from what the player has sensed. */
+#[derive(Debug, Clone, Serialize)]
+pub struct Hint {
+    /// the chances for each room (indexed from 0)
+    rooms: Vec<Chances>,
+    advice: Advice,
+    /// whether the chances were worked out exactly rather than by sampling
+    exact: bool,
+}
+impl Hint {
+    /// work out a hint for a player in room `player`
+    pub fn new(
+        cave: &Cave,
+        knowledge: &Knowledge,
+        player: u8,
+        pits: usize,
+        bats: usize,
+        wumpuses: usize,
+        dice: &mut Dice,
+    ) -> Self {
+        let problem = Problem::new(cave, knowledge, pits, bats, wumpuses);
+        let size = cave.size() as u64;
+        let most = pits.max(bats).max(wumpuses).max(1) as u64;
+        let exact = choose(size, most) <= EXACT_LIMIT;
+        let rooms = if exact {
+            problem.enumerate()
+        } else {
+            problem.sample(dice)
+        };
+        let advice = match rooms {
+            None => Advice::Confused,
+            Some(ref rooms) => advise(cave, rooms, player, &problem.standing),
+        };
+        debug!("hint advice {:?}", &advice);
+        Self {
+            rooms: rooms.unwrap_or_else(|| vec![Chances::default(); cave.size() as usize]),
+            advice,
+            exact,
+        }
+    }
+    /// the chances for each room (indexed from 0)
+    pub fn rooms(&self) -> &[Chances] {
+        &self.rooms
+    }
+    /// what the hint suggests
+    pub fn advice(&self) -> &Advice {
+        &self.advice
+    }
+    /// describe the advice for the player
+    pub fn describe(&self) -> String {
+        match &self.advice {
+            Advice::Move { room, danger } => format!(
+                "HINT: MOVE TO ROOM {} (CHANCE OF DEATH {:.0}%)",
+                room,
+                danger * 100.0
+            ),
+            Advice::Shoot { path, chance } => format!(
+                "HINT: SHOOT TO {} (CHANCE OF HITTING A WUMPUS {:.0}%)",
+                path.iter()
+                    .map(|room| room.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                chance * 100.0
+            ),
+            Advice::Confused => "HINT: I'M CONFUSED".to_string(),
+        }
+    }
+}
+/** A `Problem` is the set of constraints that any placement of the hazards must satisfy. */
+struct Problem<'a> {
+    cave: &'a Cave,
+    observations: &'a [Observation],
+    wumpus_observations: &'a [Observation],
+    /// rooms where the player has stood
+    standing: Vec<u8>,
+    /// rooms where the player has stood since the wumpus last moved
+    wumpus_standing: Vec<u8>,
+    bats_found: &'a [u8],
+    /// whether the wumpuses are still where they started, so that they cannot share a room with another hazard
+    wumpus_unmoved: bool,
+    pits: usize,
+    bats: usize,
+    wumpuses: usize,
+}
+impl<'a> Problem<'a> {
+    fn new(
+        cave: &'a Cave,
+        knowledge: &'a Knowledge,
+        pits: usize,
+        bats: usize,
+        wumpuses: usize,
+    ) -> Self {
+        Self {
+            cave,
+            observations: knowledge.observations(),
+            wumpus_observations: knowledge.wumpus_observations(),
+            standing: knowledge.observations().iter().map(|o| o.room).collect(),
+            wumpus_standing: knowledge
+                .wumpus_observations()
+                .iter()
+                .map(|o| o.room)
+                .collect(),
+            bats_found: knowledge.bats_found(),
+            wumpus_unmoved: !knowledge.wumpus_has_moved(),
+            pits,
+            bats,
+            wumpuses,
+        }
+    }
+    /// whether a placement of pits matches what the player sensed
+    fn pits_fit(&self, rooms: &[u8]) -> bool {
+        rooms
+            .iter()
+            .all(|r| !self.standing.contains(r) && !self.bats_found.contains(r))
+            && fits(self.cave, self.observations, rooms, |s| s.pits)
+    }
+    /// whether a placement of bats matches what the player sensed
+    fn bats_fit(&self, rooms: &[u8]) -> bool {
+        rooms.iter().all(|r| !self.standing.contains(r))
+            && self.bats_found.iter().all(|r| rooms.contains(r))
+            && fits(self.cave, self.observations, rooms, |s| s.bats)
+    }
+    /// whether the wumpuses being in `rooms` matches what the player sensed
+    fn wumpuses_fit(&self, rooms: &[u8]) -> bool {
+        rooms.iter().all(|r| !self.wumpus_standing.contains(r))
+            && fits(self.cave, self.wumpus_observations, rooms, |s| s.wumpus)
+    }
+    /// whether a combination of placements is possible
+    fn compatible(&self, pits: &[u8], bats: &[u8], wumpuses: &[u8]) -> bool {
+        pits.iter().all(|r| !bats.contains(r))
+            && !(self.wumpus_unmoved
+                && wumpuses
+                    .iter()
+                    .any(|wumpus| pits.contains(wumpus) || bats.contains(wumpus)))
+    }
+    /// work out the chances by trying every placement of the hazards
+    fn enumerate(&self) -> Option<Vec<Chances>> {
+        let rooms: Vec<u8> = (1..=self.cave.size()).collect();
+        let pit_sets: Vec<Vec<u8>> = combinations(&rooms, self.pits)
+            .into_iter()
+            .filter(|set| self.pits_fit(set))
+            .collect();
+        let bat_sets: Vec<Vec<u8>> = combinations(&rooms, self.bats)
+            .into_iter()
+            .filter(|set| self.bats_fit(set))
+            .collect();
+        let wumpus_sets: Vec<Vec<u8>> = combinations(&rooms, self.wumpuses)
+            .into_iter()
+            .filter(|set| self.wumpuses_fit(set))
+            .collect();
+        debug!(
+            "hint: {} pit placements, {} bat placements, {} wumpus placements",
+            pit_sets.len(),
+            bat_sets.len(),
+            wumpus_sets.len()
+        );
+        let mut tally = Tally::new(self.cave.size());
+        for pits in &pit_sets {
+            for bats in &bat_sets {
+                if !pits.iter().all(|r| !bats.contains(r)) {
+                    continue;
+                }
+                for wumpuses in &wumpus_sets {
+                    if self.compatible(pits, bats, wumpuses) {
+                        tally.add(pits, bats, wumpuses);
+                    }
+                }
+            }
+        }
+        tally.chances()
+    }
+    /// estimate the chances from random placements of the hazards
+    fn sample(&self, dice: &mut Dice) -> Option<Vec<Chances>> {
+        let rng = dice.rng();
+        let rooms: Vec<u8> = (1..=self.cave.size()).collect();
+        let mut find = |count: usize, fit: &dyn Fn(&[u8]) -> bool| -> Vec<Vec<u8>> {
+            let mut found = vec![];
+            for _ in 0..TRIES {
+                let set: Vec<u8> = rooms.choose_multiple(rng, count).cloned().collect();
+                if fit(&set) {
+                    found.push(set);
+                    if found.len() >= SAMPLES {
+                        break;
+                    }
+                }
+            }
+            found
+        };
+        let pit_sets = find(self.pits, &|set| self.pits_fit(set));
+        let bat_sets = find(self.bats, &|set| self.bats_fit(set));
+        let wumpus_sets = find(self.wumpuses, &|set| self.wumpuses_fit(set));
+        debug!(
+            "hint: sampled {} pit placements, {} bat placements, {} wumpus placements",
+            pit_sets.len(),
+            bat_sets.len(),
+            wumpus_sets.len()
+        );
+        let mut tally = Tally::new(self.cave.size());
+        if pit_sets.is_empty() || bat_sets.is_empty() || wumpus_sets.is_empty() {
+            return None;
+        }
+        for _ in 0..TRIES {
+            let pits = pit_sets.choose(rng)?;
+            let bats = bat_sets.choose(rng)?;
+            let wumpuses = wumpus_sets.choose(rng)?;
+            if self.compatible(pits, bats, wumpuses) {
+                tally.add(pits, bats, wumpuses);
+            }
+        }
+        tally.chances()
+    }
+}
+/** A `Tally` counts how often each room holds each hazard over a set of placements. */
+struct Tally {
+    total: f64,
+    counts: Vec<Chances>,
+}
+impl Tally {
+    fn new(size: u8) -> Self {
+        Self {
+            total: 0.0,
+            counts: vec![Chances::default(); size as usize],
+        }
+    }
+    fn add(&mut self, pits: &[u8], bats: &[u8], wumpuses: &[u8]) {
+        self.total += 1.0;
+        for r in pits {
+            self.counts[*r as usize - 1].pit += 1.0;
+        }
+        for r in bats {
+            self.counts[*r as usize - 1].bats += 1.0;
+        }
+        for r in wumpuses {
+            self.counts[*r as usize - 1].wumpus += 1.0;
+        }
+    }
+    /// the chances, if any placement was possible
+    fn chances(self) -> Option<Vec<Chances>> {
+        if self.total == 0.0 {
+            return None;
+        }
+        let total = self.total;
+        Some(
+            self.counts
+                .into_iter()
+                .map(|c| Chances {
+                    wumpus: c.wumpus / total,
+                    pit: c.pit / total,
+                    bats: c.bats / total,
+                })
+                .collect(),
+        )
+    }
+}
+/// whether hazards in `rooms` would give the warnings that the player sensed
+fn fits(
+    cave: &Cave,
+    observations: &[Observation],
+    rooms: &[u8],
+    count: impl Fn(&Senses) -> u8,
+) -> bool {
+    observations.iter().all(|o| {
+        cave.tunnels(o.room)
+            .iter()
+            .filter(|to| rooms.contains(to))
+            .count()
+            == count(&o.senses) as usize
+    })
+}
+/// the number of ways of choosing `k` of `n` things
+fn choose(n: u64, k: u64) -> u64 {
+    (0..k.min(n)).fold(1, |c: u64, i| c.saturating_mul(n - i) / (i + 1))
+}
+/// all the ways of choosing `k` of `items`
+fn combinations(items: &[u8], k: usize) -> Vec<Vec<u8>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    let mut result = vec![];
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, *item);
+            result.push(rest);
+        }
+    }
+    result
+}
+/// choose between shooting and moving (preferring rooms that have not been visited)
+fn advise(cave: &Cave, rooms: &[Chances], player: u8, visited: &[u8]) -> Advice {
+    let mut best_path = vec![];
+    let mut best_chance = 0.0;
+    let mut path = vec![];
+    arrow_paths(
+        cave,
+        rooms,
+        player,
+        player,
+        &mut path,
+        &mut |path, chance| {
+            if chance > best_chance + 1e-9 {
+                best_chance = chance;
+                best_path = path.to_vec();
+            }
+        },
+    );
+    if best_chance >= SHOOT_CHANCE {
+        return Advice::Shoot {
+            path: best_path,
+            chance: best_chance,
+        };
+    }
+    let mut best_room = 0;
+    let mut best_danger = f64::INFINITY;
+    for room in cave.tunnels(player) {
+        let chances = rooms[*room as usize - 1];
+        let revisit = if visited.contains(room) { 0.001 } else { 0.0 };
+        let danger = chances.danger() + chances.bats * 0.01 + revisit;
+        if danger < best_danger {
+            best_danger = danger;
+            best_room = *room;
+        }
+    }
+    Advice::Move {
+        room: best_room,
+        danger: rooms[best_room as usize - 1].danger(),
+    }
+}
+/// try every path an arrow can take from `from` (up to 5 rooms), with the chance of hitting the wumpus
+fn arrow_paths(
+    cave: &Cave,
+    rooms: &[Chances],
+    player: u8,
+    from: u8,
+    path: &mut Vec<u8>,
+    found: &mut dyn FnMut(&[u8], f64),
+) {
+    if path.len() >= 5 {
+        return;
+    }
+    for to in cave.tunnels(from) {
+        let k = path.len();
+        if *to == player || (k > 1 && path[k - 2] == *to) {
+            continue;
+        }
+        path.push(*to);
+        let mut visited: Vec<u8> = path.clone();
+        visited.sort_unstable();
+        visited.dedup();
+        let chance = visited
+            .iter()
+            .map(|room| rooms[*room as usize - 1].wumpus)
+            .sum::<f64>()
+            .min(1.0);
+        found(path, chance);
+        arrow_paths(cave, rooms, player, *to, path, found);
+        path.pop();
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hint(knowledge: &Knowledge) -> Hint {
+        let mut dice = Dice::new(Some(1));
+        Hint::new(&Cave::dodecahedron(), knowledge, 1, 2, 2, 1, &mut dice)
+    }
+    #[test]
+    fn chances_add_up_to_the_hazards() {
+        let mut knowledge = Knowledge::default();
+        knowledge.observe(1, Senses::default());
+        let hint = hint(&knowledge);
+        assert!(hint.exact);
+        let total = |chance: fn(&Chances) -> f64| hint.rooms().iter().map(chance).sum::<f64>();
+        assert!((total(|c| c.pit) - 2.0).abs() < 1e-9);
+        assert!((total(|c| c.bats) - 2.0).abs() < 1e-9);
+        assert!((total(|c| c.wumpus) - 1.0).abs() < 1e-9);
+        for room in [1, 2, 5, 8] {
+            assert_eq!(hint.rooms()[room - 1].danger(), 0.0);
+        }
+        assert!(matches!(hint.advice(), Advice::Move { danger, .. } if *danger == 0.0));
+    }
+    #[test]
+    fn smelt_wumpus_is_equally_likely_in_each_room_next_door() {
+        let mut knowledge = Knowledge::default();
+        knowledge.observe(
+            1,
+            Senses {
+                wumpus: 1,
+                ..Senses::default()
+            },
+        );
+        let hint = hint(&knowledge);
+        for room in [2, 5, 8] {
+            assert!((hint.rooms()[room - 1].wumpus - 1.0 / 3.0).abs() < 1e-9);
+        }
+        assert_eq!(hint.rooms()[2].wumpus, 0.0);
+    }
+    #[test]
+    fn impossible_senses_confuse() {
+        let mut knowledge = Knowledge::default();
+        knowledge.observe(
+            1,
+            Senses {
+                pits: 3,
+                bats: 3,
+                ..Senses::default()
+            },
+        );
+        assert!(matches!(hint(&knowledge).advice(), Advice::Confused));
+        assert_eq!(choose(20, 2), 190);
+        assert_eq!(combinations(&[1, 2, 3], 2), [[1, 2], [1, 3], [2, 3]]);
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
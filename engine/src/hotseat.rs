@@ -0,0 +1,53 @@
+/*! hot-seat games, where several players take turns in the same cave */
+
+use crate::{knowledge::Knowledge, Cause, Resources};
+use serde::Serialize;
+
+/// the most players that can share a cave
+pub const MAX_PLAYERS: usize = 4;
+
+/** A `Player` is one of the players in a hot-seat game who is waiting for their turn (or who is out). The player whose turn it is is kept in the game data itself. */
+#[derive(Debug, Clone)]
+pub struct Player {
+    /// the player's number, from 1
+    pub number: usize,
+    pub room: u8,
+    pub arrows: i8,
+    /// which rooms the player has been in (indexed from 0)
+    pub visited: Vec<bool>,
+    pub knowledge: Knowledge,
+    /// the number of wumpuses the player has killed
+    pub killed: usize,
+    /// how the player went out of the game, if they did
+    pub out: Option<Cause>,
+    /// the lamp oil and food the player has left
+    pub left: Resources,
+}
+/** A `PlayerPanel` is what everyone can see about a player in a hot-seat game. */
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerPanel {
+    /// the player's number, from 1
+    pub number: usize,
+    pub arrows: i8,
+    /// the number of wumpuses the player has killed
+    pub killed: usize,
+    /// how the player went out of the game, if they did
+    pub out: Option<Cause>,
+    /// whether it is this player's turn
+    pub current: bool,
+}
+impl Player {
+    /// what everyone can see about the player
+    pub fn panel(&self) -> PlayerPanel {
+        PlayerPanel {
+            number: self.number,
+            arrows: self.arrows,
+            killed: self.killed,
+            out: self.out,
+            current: false,
+        }
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,256 @@
+/*! what the player knows about the cave, worked out from what they have sensed */
+
+use crate::Cave;
+use log::debug;
+use serde::Serialize;
+
+/** `Senses` are what the player notices in a room: the number of tunnels that lead to each kind of hazard. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub struct Senses {
+    /// 'I SMELL A WUMPUS'
+    pub wumpus: u8,
+    /// 'I FEEL A DRAFT'
+    pub pits: u8,
+    /// 'BATS NEARBY'
+    pub bats: u8,
+}
+/** An `Observation` is what the player sensed at the end of a turn. */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// where the player was
+    pub room: u8,
+    pub senses: Senses,
+}
+/** A `Suspicion` is what the player knows about whether a room holds a hazard. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub enum Suspicion {
+    /// nothing is known
+    #[default]
+    Unknown,
+    /// the hazard cannot be there
+    No,
+    /// the hazard could be there
+    Possible,
+    /// the hazard must be there
+    Certain,
+}
+/** `RoomKnowledge` is what the player can work out about one room. */
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RoomKnowledge {
+    /// whether the player has been in the room
+    pub visited: bool,
+    /// whether the room is known to hold no hazards
+    pub safe: bool,
+    pub pit: Suspicion,
+    pub bats: Suspicion,
+    pub wumpus: Suspicion,
+}
+/** `Knowledge` is everything the player has found out in the current game. */
+#[derive(Debug, Default, Clone)]
+pub struct Knowledge {
+    /// what the player sensed, in order
+    observations: Vec<Observation>,
+    /// the first observation made since the wumpus last moved
+    wumpus_since: usize,
+    /// rooms where the bats snatched the player
+    bats_found: Vec<u8>,
+}
+impl Knowledge {
+    /// forget everything (for a new game)
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+    /// note what the player sensed
+    pub fn observe(&mut self, room: u8, senses: Senses) {
+        let observation = Observation { room, senses };
+        if self.observations.len() > self.wumpus_since
+            && self.observations.last() == Some(&observation)
+        {
+            return;
+        }
+        debug!("observed {:?}", &observation);
+        self.observations.push(observation);
+    }
+    /// note that the player was snatched by bats in `room`
+    pub fn found_bats(&mut self, room: u8) {
+        if !self.bats_found.contains(&room) {
+            self.bats_found.push(room);
+        }
+    }
+    /// note that the wumpus has woken, so it may not be where it was
+    pub fn wumpus_moved(&mut self) {
+        self.wumpus_since = self.observations.len();
+    }
+    /// everything the player has sensed
+    pub fn observations(&self) -> &[Observation] {
+        &self.observations
+    }
+    /// rooms where the player has been snatched by bats
+    pub fn bats_found(&self) -> &[u8] {
+        &self.bats_found
+    }
+    /// whether the wumpus has moved since the start of the game
+    pub fn wumpus_has_moved(&self) -> bool {
+        self.wumpus_since > 0
+    }
+    /// what the player has sensed since the wumpus last moved
+    pub fn wumpus_observations(&self) -> &[Observation] {
+        &self.observations[self.wumpus_since..]
+    }
+    /// work out what is known about each room, given how many pits, bats and wumpuses there are
+    pub fn rooms(
+        &self,
+        cave: &Cave,
+        pits: usize,
+        bats: usize,
+        wumpuses: usize,
+    ) -> Vec<RoomKnowledge> {
+        let size = cave.size() as usize;
+        let mut rooms = vec![RoomKnowledge::default(); size];
+        for observation in &self.observations {
+            rooms[observation.room as usize - 1].visited = true;
+        }
+        for room in &self.bats_found {
+            rooms[*room as usize - 1].visited = true;
+        }
+        let standing: Vec<u8> = self.observations.iter().map(|o| o.room).collect();
+        let mut pit_clear = standing.clone();
+        pit_clear.extend(self.bats_found.iter());
+        let pit = deduce(cave, &self.observations, |s| s.pits, &pit_clear, &[], pits);
+        let bats = deduce(
+            cave,
+            &self.observations,
+            |s| s.bats,
+            &standing,
+            &self.bats_found,
+            bats,
+        );
+        let wumpus_standing: Vec<u8> = self.wumpus_observations().iter().map(|o| o.room).collect();
+        let wumpus = deduce(
+            cave,
+            self.wumpus_observations(),
+            |s| s.wumpus,
+            &wumpus_standing,
+            &[],
+            wumpuses,
+        );
+        for (i, room) in rooms.iter_mut().enumerate() {
+            room.pit = pit[i];
+            room.bats = bats[i];
+            room.wumpus = wumpus[i];
+            room.safe = room.pit == Suspicion::No
+                && room.bats == Suspicion::No
+                && room.wumpus == Suspicion::No;
+        }
+        rooms
+    }
+}
+/// work out where one kind of hazard can be
+fn deduce(
+    cave: &Cave,
+    observations: &[Observation],
+    count: impl Fn(&Senses) -> u8,
+    clear: &[u8],
+    found: &[u8],
+    total: usize,
+) -> Vec<Suspicion> {
+    let size = cave.size() as usize;
+    let mut suspicion = vec![Suspicion::Unknown; size];
+    for room in clear {
+        suspicion[*room as usize - 1] = Suspicion::No;
+    }
+    for observation in observations {
+        if count(&observation.senses) == 0 {
+            for to in cave.tunnels(observation.room) {
+                suspicion[*to as usize - 1] = Suspicion::No;
+            }
+        }
+    }
+    for observation in observations {
+        let sensed = count(&observation.senses) as usize;
+        if sensed == 0 {
+            continue;
+        }
+        let candidates: Vec<u8> = cave
+            .tunnels(observation.room)
+            .iter()
+            .filter(|to| suspicion[**to as usize - 1] != Suspicion::No)
+            .cloned()
+            .collect();
+        for to in &candidates {
+            let s = &mut suspicion[*to as usize - 1];
+            if candidates.len() <= sensed {
+                *s = Suspicion::Certain;
+            } else if *s == Suspicion::Unknown {
+                *s = Suspicion::Possible;
+            }
+        }
+    }
+    for room in found {
+        suspicion[*room as usize - 1] = Suspicion::Certain;
+    }
+    if suspicion
+        .iter()
+        .filter(|s| **s == Suspicion::Certain)
+        .count()
+        >= total
+    {
+        for s in suspicion.iter_mut() {
+            if *s != Suspicion::Certain {
+                *s = Suspicion::No;
+            }
+        }
+    }
+    suspicion
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn senses(wumpus: u8, pits: u8, bats: u8) -> Senses {
+        Senses { wumpus, pits, bats }
+    }
+    #[test]
+    fn rooms_next_to_no_draft_have_no_pit() {
+        let cave = Cave::dodecahedron();
+        let mut knowledge = Knowledge::default();
+        knowledge.observe(1, senses(0, 0, 0));
+        let rooms = knowledge.rooms(&cave, 2, 2, 1);
+        for room in [1, 2, 5, 8] {
+            assert!(rooms[room - 1].safe, "room {} should be safe", room);
+        }
+        assert_eq!(rooms[2].pit, Suspicion::Unknown);
+    }
+    #[test]
+    fn only_room_left_for_a_pit_must_hold_it() {
+        let cave = Cave::dodecahedron();
+        let mut knowledge = Knowledge::default();
+        // room 2 leads to 1, 3 and 10; a draft there, with 1 and 3 known to be clear, puts the pit in 10
+        knowledge.observe(1, senses(0, 0, 0));
+        knowledge.observe(2, senses(0, 1, 0));
+        let rooms = knowledge.rooms(&cave, 2, 2, 1);
+        assert_eq!(rooms[2].pit, Suspicion::Possible);
+        knowledge.observe(3, senses(0, 0, 0));
+        let rooms = knowledge.rooms(&cave, 2, 2, 1);
+        assert_eq!(rooms[9].pit, Suspicion::Certain);
+        assert_eq!(rooms[2].pit, Suspicion::No);
+    }
+    #[test]
+    fn wumpus_is_forgotten_once_it_moves() {
+        let cave = Cave::dodecahedron();
+        let mut knowledge = Knowledge::default();
+        knowledge.observe(1, senses(0, 0, 0));
+        assert_eq!(knowledge.rooms(&cave, 2, 2, 1)[1].wumpus, Suspicion::No);
+        knowledge.wumpus_moved();
+        assert!(knowledge.wumpus_has_moved());
+        assert_eq!(
+            knowledge.rooms(&cave, 2, 2, 1)[1].wumpus,
+            Suspicion::Unknown
+        );
+        knowledge.found_bats(5);
+        assert_eq!(knowledge.rooms(&cave, 2, 2, 1)[4].bats, Suspicion::Certain);
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
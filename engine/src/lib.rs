@@ -1,36 +1,262 @@
 /*! engine for old wumpus game */
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use askama::Template;
-use getset::{CopyGetters, Getters};
+use getset::{CopyGetters, Getters, Setters};
 use log::debug;
 use narthex_engine_trait::{
     ActionTrait, ConfigTrait, EngineTrait, Event, InterfaceType, ResponseTrait,
 };
 use serde::{Deserialize, Serialize};
-use std::fmt;
+use std::{collections::VecDeque, fmt, path::PathBuf};
 use strum::EnumString;
-/** we might not need a config but we need to declare one  */
-#[derive(Default, Deserialize, Debug)]
-pub struct Config {}
+mod aim;
+mod cave;
+mod daily;
+mod dice;
+mod editor;
+mod events;
+mod hazard;
+mod hint;
+mod hotseat;
+mod knowledge;
+mod map;
+mod puzzle;
+mod replay;
+mod resources;
+mod scenario;
+mod score;
+mod spectator;
+mod stats;
+mod tutorial;
+mod wumpus;
+pub use aim::{AimView, MAX_ARROW_PATH};
+use aim::is_crooked;
+pub use cave::{Cave, WUMPUS_II_CAVES};
+pub use daily::Daily;
+use dice::Dice;
+pub use editor::{CaveFile, EditorView, Placement};
+use editor::Editor;
+pub use events::{GameEvent, Observer};
+use events::Observers;
+use hazard::{Effect, Placed, Sense};
+pub use hint::{Advice, Chances, Hint};
+use hotseat::{Player, MAX_PLAYERS};
+pub use hotseat::PlayerPanel;
+use knowledge::Knowledge;
+pub use knowledge::{RoomKnowledge, Senses, Suspicion};
+use map::Map;
+pub use puzzle::{Puzzle, PuzzlePack, PuzzleView};
+use replay::{Frame, Recording, ReplayView};
+pub use replay::HazardRooms;
+pub use resources::Resources;
+use resources::LOW;
+use scenario::{Scenario, Special};
+use score::Play;
+pub use score::{HighScore, HighScores};
+pub use spectator::{SpectatedPlayer, SpectatorView};
+use stats::GameTally;
+pub use stats::{Achievement, Stats};
+pub use tutorial::LessonView;
+use wumpus::{Noise, Surroundings, WumpusBehaviour};
+pub use wumpus::WumpusKind;
+/** The `Config` sets up the game. */
+#[derive(Deserialize, Debug, Clone, Getters, CopyGetters, Setters)]
+#[serde(default)]
+#[getset(set = "pub")]
+pub struct Config {
+    /// the cave to play in, if not the classic dodecahedron
+    #[getset(get = "pub")]
+    cave: Option<Cave>,
+    /// whether to play without being shown what can be worked out from the warnings
+    #[getset(get_copy = "pub")]
+    purist: bool,
+    /// the seed for the dice, so that games can be repeated
+    seed: Option<u64>,
+    /// the number of arrows the player starts with
+    #[getset(get_copy = "pub")]
+    arrows: i8,
+    /// the number of rooms with bottomless pits
+    #[getset(get_copy = "pub")]
+    pits: usize,
+    /// the number of rooms with super bats
+    #[getset(get_copy = "pub")]
+    bats: usize,
+    /// how the wumpus behaves
+    #[getset(get_copy = "pub")]
+    wumpus: WumpusKind,
+    /// which rules to play by
+    #[getset(get_copy = "pub")]
+    variant: Variant,
+    /// the number of wumpuses
+    #[getset(get_copy = "pub")]
+    wumpuses: usize,
+    /// what the player has to do to win
+    #[getset(get_copy = "pub")]
+    goal: Goal,
+    /// whether arrows that miss can be picked up again
+    #[getset(get_copy = "pub")]
+    arrow_pickup: bool,
+    /// the number of rooms with a quiver of arrows in them
+    #[getset(get_copy = "pub")]
+    quivers: usize,
+    /// the number of arrows in each quiver
+    #[getset(get_copy = "pub")]
+    quiver_arrows: i8,
+    /// the lamp oil and food the player has
+    #[getset(get_copy = "pub")]
+    resources: Resources,
+    /// the file to keep the high scores in, if they are kept in a file
+    #[getset(get = "pub")]
+    high_scores_file: Option<PathBuf>,
+    /// the file to keep the player's statistics in, if they are kept in a file
+    #[getset(get = "pub")]
+    stats_file: Option<PathBuf>,
+    /// the number of players taking turns in the same cave
+    #[getset(get_copy = "pub")]
+    players: usize,
+    /// files of puzzle packs (JSON) to offer as well as the ones that come with the game
+    #[getset(get = "pub")]
+    puzzle_files: Vec<PathBuf>,
+    /// the file to keep the cave designed in the editor in (see [CaveFile]), if it is kept in a file
+    #[getset(get = "pub")]
+    cave_file: Option<PathBuf>,
+}
+/** A `Goal` is what the player has to do to win. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Goal {
+    /// kill every wumpus
+    #[default]
+    KillAll,
+    /// kill any one wumpus
+    KillAny,
+    /// kill every wumpus within a number of turns
+    KillAllWithin { turns: u32 },
+}
+/** A `Variant` is a set of rules to play by. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Variant {
+    /// the original game
+    #[default]
+    Classic,
+    /// the sequel: a choice of caves, some with one-way tunnels, or a cave of the player's own design
+    WumpusII,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cave: None,
+            purist: false,
+            seed: None,
+            arrows: 5,
+            pits: 2,
+            bats: 2,
+            wumpus: WumpusKind::Classic,
+            variant: Variant::Classic,
+            wumpuses: 1,
+            goal: Goal::KillAll,
+            arrow_pickup: false,
+            quivers: 0,
+            quiver_arrows: 5,
+            resources: Resources::default(),
+            high_scores_file: None,
+            stats_file: None,
+            puzzle_files: vec![],
+            cave_file: None,
+            players: 1,
+        }
+    }
+}
 impl ConfigTrait for Config {
-    fn from_json(_json_str: &str) -> Result<Self>
+    fn from_json(json_str: &str) -> Result<Self>
     where
         Self: Sized,
     {
-        Ok(Self {})
+        Ok(serde_json::from_str(json_str)?)
     }
 }
 
 /** An `Action`  is something that the [Engine] does. */
-#[derive(Debug, Deserialize, EnumString, Clone)]
+#[derive(Debug, Deserialize, Serialize, EnumString, Clone, PartialEq)]
 #[repr(C)]
 pub enum Action {
     Move(u8),
     Shoot(Vec<u8>),
+    /// choose the rooms to shoot an arrow through, one at a time: check those chosen so far and show which rooms the arrow can be aimed at next (see [AimView])
+    Aim(Vec<u8>),
     ReStart,
     Instructions,
     Quit,
+    /// replay the current game from the start
+    Replay,
+    /// show the next turn of the replay
+    ReplayNext,
+    /// show the previous turn of the replay
+    ReplayPrevious,
+    /// stop replaying and go back to the game
+    ReplayEnd,
+    /// whether to show what the player knows about each room
+    ShowKnowledge(bool),
+    /// suggest what to do next
+    Hint,
+    /// start a new game in one of the caves of Wumpus II (see [WUMPUS_II_CAVES])
+    ChooseCave(u8),
+    /// start a new game in a cave designed by the player (Wumpus II only)
+    DesignCave(Cave),
+    /// show the table of high scores
+    ShowHighScores,
+    /// show the player's statistics and achievements
+    ShowStats,
+    /// let the turn go by without moving or shooting (for a player who has run out of time to choose, in a networked game)
+    Pass,
+    /// start today's daily challenge (see [Daily])
+    StartDaily,
+    /// start the tutorial from the first lesson
+    StartTutorial,
+    /// start a puzzle, by the numbers of its pack and of the puzzle in the pack (from 0)
+    StartPuzzle(usize, usize),
+    /// open the cave editor
+    EditCave,
+    /// add a room to the cave being designed
+    AddRoom,
+    /// remove a room from the cave being designed; the rooms after it are numbered one less
+    RemoveRoom(u8),
+    /// add a tunnel to the cave being designed, and another one back if `both_ways`
+    AddTunnel { from: u8, to: u8, both_ways: bool },
+    /// remove a tunnel from the cave being designed, and the one back if `both_ways`
+    RemoveTunnel { from: u8, to: u8, both_ways: bool },
+    /// put a wumpus or a hazard in a room of the cave being designed, or clear the room
+    PlaceInRoom(u8, Placement),
+    /// choose the room the player starts in, in the cave being designed
+    SetStart(u8),
+    /// check the cave being designed and save it (see [CaveFile])
+    SaveCave,
+    /// play the cave being designed
+    Playtest,
+    /// close the cave editor and go back to the game
+    StopEditing,
+}
+impl Action {
+    /// whether the action changes the game (including the dice), so that it must be played again to check a score
+    fn changes_game(&self) -> bool {
+        matches!(self, Self::Move(_) | Self::Shoot(_) | Self::Pass | Self::Hint)
+    }
+    /// whether the action is for the cave editor
+    fn edits(&self) -> bool {
+        matches!(
+            self,
+            Self::EditCave
+                | Self::AddRoom
+                | Self::RemoveRoom(_)
+                | Self::AddTunnel { .. }
+                | Self::RemoveTunnel { .. }
+                | Self::PlaceInRoom(..)
+                | Self::SetStart(_)
+                | Self::SaveCave
+                | Self::Playtest
+                | Self::StopEditing
+        )
+    }
 }
 impl ActionTrait for Action {
     fn from_json(json_str: &str) -> Result<Self>
@@ -45,84 +271,410 @@ impl ActionTrait for Action {
 pub struct Engine {
     interface_type: InterfaceType,
     data: Data,
+    /// the file to keep the high scores in, if they are kept in a file
+    high_scores_file: Option<PathBuf>,
+    /// the file to keep the player's statistics in, if they are kept in a file
+    stats_file: Option<PathBuf>,
+    /// the lesson of the tutorial or the puzzle being played, if one is
+    special: Option<Special>,
+    /// the packs of puzzles the player can choose from
+    puzzle_packs: Vec<PuzzlePack>,
+    /// the cave editor
+    editor: Editor,
+    /// those told about what happens in the game
+    observers: Observers,
 }
 impl EngineTrait for Engine {
     type Action = Action;
     type Response = Response;
     type Config = Config;
     /// create a new [Engine].
-    fn new(_config: &Self::Config, interface_type: InterfaceType) -> Result<Self> {
+    fn new(config: &Self::Config, interface_type: InterfaceType) -> Result<Self> {
         debug!("creating new game engine");
+        let cave = config.cave.clone().unwrap_or_default();
+        cave.check()?;
+        check_space(
+            &cave,
+            config.players,
+            config.wumpuses + config.pits + config.bats + config.quivers,
+        )?;
+        if config.arrows < 1 {
+            return Err(anyhow!("the player needs at least one arrow"));
+        }
+        if config.wumpuses < 1 {
+            return Err(anyhow!("there must be at least one wumpus"));
+        }
+        if config.players < 1 || config.players > MAX_PLAYERS {
+            return Err(anyhow!(
+                "there must be between 1 and {} players, not {}",
+                MAX_PLAYERS,
+                config.players
+            ));
+        }
+        let editor = Editor::new(&cave, config.cave_file.clone())?;
+        let mut data = Data::new(cave, config);
+        if let Some(path) = &config.high_scores_file {
+            data.high_scores = HighScores::load(path)?;
+        }
+        if let Some(path) = &config.stats_file {
+            data.stats = Stats::load(path)?;
+        }
+        let mut puzzle_packs = PuzzlePack::shipped()?;
+        for path in &config.puzzle_files {
+            puzzle_packs.push(PuzzlePack::load(path)?);
+        }
         Ok(Self {
             interface_type,
-            data: Data::new(),
-            ..Engine::default()
+            data,
+            high_scores_file: config.high_scores_file.clone(),
+            stats_file: config.stats_file.clone(),
+            special: None,
+            puzzle_packs,
+            editor,
+            observers: Observers::default(),
         })
     }
     /** `initial_html` provides the initial HTML. */
     fn initial_html(&mut self) -> Result<String> {
         let template = InitialTemplate {
             interface_type: self.interface_type,
-            ..Default::default()
+            wumpus_ii: self.data.variant == Variant::WumpusII,
+            caves: &WUMPUS_II_CAVES,
+            puzzle_packs: &self.puzzle_packs,
         };
         Ok(template.render()?)
     }
     /** `execute` executes the user command ([Action]) and returns a [Response]. */
     fn execute(&mut self, action: Action) -> Result<Response> {
         debug!("executing {:?}...", &action);
+        let response = if self.editor.editing() || action.edits() {
+            self.edit(action)
+        } else if self.special.is_some() {
+            self.play_scenario(action)
+        } else {
+            self.play(action)
+        };
+        let events = std::mem::take(&mut self.data.events);
+        self.observers.notify(&events);
+        response
+    }
+    fn handle_event(&mut self, event: &Event) -> Result<Self::Response> {
+        match event {
+            Event::Create => {
+                /* do nothing  */
+                Ok(Response::default())
+            }
+            Event::SaveInstanceState => Ok(Response {
+                tag_vec: vec![
+                    ("high_scores".to_string(), self.data.high_scores.to_json()?),
+                    ("stats".to_string(), self.data.stats.to_json()?),
+                    ("cave".to_string(), self.editor.design().to_json()?),
+                ],
+                ..Response::default()
+            }),
+            Event::RestoreInstanceState(state) => {
+                if let Some(json) = state.get("high_scores") {
+                    self.data.high_scores = HighScores::from_json(json)?;
+                }
+                if let Some(json) = state.get("stats") {
+                    self.data.stats = Stats::from_json(json)?;
+                }
+                if let Some(json) = state.get("cave") {
+                    self.editor.restore(json)?;
+                }
+                Ok(Response::default())
+            }
+            _ => {
+                debug!("event ignored {:?}", &event);
+                Ok(Response::default())
+            } // TODO add
+        }
+    }
+
+    /// interface type
+    fn get_interface_type(&self) -> InterfaceType {
+        self.interface_type
+    }
+}
+impl Engine {
+    /// carry out an action in the game being played
+    fn play(&mut self, action: Action) -> Result<Response> {
         self.data.msgs.clear();
+        self.data.handover = 0;
+        self.data.hint = None;
+        if self.data.f == 0 && action.changes_game() {
+            self.data.actions.push(action.clone());
+        }
         match action {
             Action::Instructions => {
                 self.data.show_instructions();
                 Ok(self.data.create_response())
             }
+            Action::Move(_) | Action::Shoot(_) | Action::Pass | Action::Aim(_)
+                if self.data.f != 0 =>
+            {
+                self.data.say("THE GAME IS OVER - RESTART TO PLAY AGAIN");
+                Ok(self.data.create_response())
+            }
             Action::Move(cave) => {
-                self.data.move_to(cave);
+                if !self.data.move_to(cave) {
+                    return Ok(self.data.create_response());
+                }
                 debug!("moved");
+                self.end_turn(true, &format!("MOVE TO {}", cave))
+            }
+            Action::Shoot(path) if is_crooked(&path) => {
+                /* 3105 */
+                self.data
+                    .say("ARROWS AREN'T THAT CROOKED - TRY ANOTHER ROOM");
                 Ok(self.data.create_response())
             }
             Action::Shoot(path) => {
-                self.data.shoot_arrow(path);
+                let label = format!(
+                    "SHOOT TO {}",
+                    path.iter()
+                        .map(|room| room.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                if !self.data.shoot_arrow(path) {
+                    return Ok(self.data.create_response());
+                }
                 debug!("arrow shot");
-                Ok(self.data.create_response())
+                self.end_turn(false, &label)
+            }
+            Action::Aim(path) => {
+                let aim = self.data.aim(path);
+                Ok(Response {
+                    aim: Some(aim),
+                    ..self.data.create_response()
+                })
+            }
+            Action::Pass => {
+                if self.data.player_count > 1 {
+                    self.data
+                        .say(&format!("PLAYER {} LETS THE TURN GO BY", self.data.player));
+                }
+                self.data.move_wumpus(Noise::Footsteps);
+                self.end_turn(false, "PASS")
             }
             Action::ReStart => {
                 self.data.renew();
                 Ok(self.data.create_response())
             }
+            Action::StartDaily => {
+                self.data.start_daily(Daily::today());
+                Ok(self.data.create_response())
+            }
+            Action::StartTutorial => self.start_scenario(Scenario::Lesson(0), vec![]),
+            Action::StartPuzzle(pack, number) => {
+                self.start_scenario(Scenario::Puzzle { pack, number }, vec![])
+            }
             Action::Quit => Ok(Response {
                 shutdown_required: true,
                 ..Response::default()
             }),
+            Action::Replay | Action::ReplayNext | Action::ReplayPrevious if self.data.f == 0 => {
+                self.data.say("YOU CAN REPLAY THE GAME ONCE IT IS OVER");
+                Ok(self.data.create_response())
+            }
+            Action::Replay => Ok(self.data.recording.start(&self.data.cave)),
+            Action::ReplayNext => Ok(self.data.recording.step(&self.data.cave, 1)),
+            Action::ReplayPrevious => Ok(self.data.recording.step(&self.data.cave, -1)),
+            Action::ReplayEnd => Ok(self.data.create_response()),
+            Action::ShowKnowledge(show) => {
+                self.data.purist = !show;
+                Ok(self.data.create_response())
+            }
+            Action::Hint => {
+                self.data.give_hint();
+                Ok(self.data.create_response())
+            }
+            Action::ChooseCave(_) | Action::DesignCave(_)
+                if self.data.variant != Variant::WumpusII =>
+            {
+                self.data.say("THERE IS ONLY ONE CAVE IN THE CLASSIC GAME");
+                Ok(self.data.create_response())
+            }
+            Action::ChooseCave(number) => {
+                match Cave::wumpus_ii(number) {
+                    Some(cave) => {
+                        self.data.change_cave(cave);
+                    }
+                    None => self.data.say("NO SUCH CAVE"),
+                }
+                Ok(self.data.create_response())
+            }
+            Action::DesignCave(cave) => {
+                self.data.change_cave(cave);
+                Ok(self.data.create_response())
+            }
+            Action::ShowHighScores => {
+                self.data.show_high_scores();
+                Ok(Response {
+                    high_scores: Some(self.data.high_scores.scores().to_vec()),
+                    ..self.data.create_response()
+                })
+            }
+            Action::ShowStats => {
+                self.data.show_stats();
+                Ok(Response {
+                    stats: Some(self.data.stats.clone()),
+                    ..self.data.create_response()
+                })
+            }
+            Action::EditCave
+            | Action::AddRoom
+            | Action::RemoveRoom(_)
+            | Action::AddTunnel { .. }
+            | Action::RemoveTunnel { .. }
+            | Action::PlaceInRoom(..)
+            | Action::SetStart(_)
+            | Action::SaveCave
+            | Action::Playtest
+            | Action::StopEditing => self.edit(action),
         }
     }
-    fn handle_event(&mut self, event: &Event) -> Result<Self::Response> {
-        match event {
-            Event::Create => {
-                /* do nothing  */
-                Ok(Response::default())
+    /** `verify` checks the score claimed for a daily challenge, by playing its actions again from its seed. `config` must have the same settings as the game that was played. */
+    pub fn verify(config: &Config, entry: &HighScore) -> Result<bool> {
+        let daily = entry
+            .daily
+            .clone()
+            .ok_or_else(|| anyhow!("only the scores of daily challenges can be checked"))?;
+        let mut config = config.clone();
+        config.high_scores_file = None;
+        config.stats_file = None;
+        let mut engine = Self::new(&config, InterfaceType::PC)?;
+        engine.data.start_daily(daily);
+        let mut score = None;
+        for action in &entry.actions {
+            score = engine.execute(action.clone())?.score();
+        }
+        Ok(score == Some(entry.score))
+    }
+    /** `observe` registers an [Observer], to be told about each [GameEvent] from now on. */
+    pub fn observe(&mut self, observer: Box<dyn Observer>) {
+        self.observers.add(observer);
+    }
+    /** `spectate` shows everything about the game after the latest [Action], including where the wumpuses and hazards are, for a spectator; players see only their [Response]. */
+    pub fn spectate(&self) -> SpectatorView {
+        SpectatorView::new(&self.data)
+    }
+    /// end the turn once the player has moved (if `moved`), shot or passed: use up their resources, check the time, and hand over to the next player, recording the turn as `label`
+    fn end_turn(&mut self, moved: bool, label: &str) -> Result<Response> {
+        self.data.turn += 1;
+        self.data.spend(moved);
+        self.data.check_time();
+        self.finish()?;
+        self.data.next_player();
+        let response = self.data.create_response();
+        self.data.record(label);
+        Ok(response)
+    }
+    /// score the game if it has just been won, and keep the high scores and statistics if they have changed
+    fn finish(&mut self) -> Result<()> {
+        if self.special.is_some() {
+            return Ok(());
+        }
+        if self.data.finish() {
+            if let Some(path) = &self.high_scores_file {
+                self.data.high_scores.save(path)?;
             }
-            Event::SaveInstanceState => {
-                /* TO DO */
-                Ok(Response::default())
+        }
+        if self.data.add_to_stats() {
+            if let Some(path) = &self.stats_file {
+                self.data.stats.save(path)?;
             }
-            _ => {
-                debug!("event ignored {:?}", &event);
-                Ok(Response::default())
-            } // TODO add
         }
-    }
-
-    /// interface type
-    fn get_interface_type(&self) -> InterfaceType {
-        self.interface_type
+        Ok(())
     }
 }
 /** template for generating the initial panel */
 #[derive(Template, Default)]
 #[template(path = "initial.html")]
-struct InitialTemplate {
+struct InitialTemplate<'a> {
     interface_type: InterfaceType,
+    /// whether to show the choice of caves
+    wumpus_ii: bool,
+    caves: &'a [&'a str],
+    /// the puzzles the player can choose from
+    puzzle_packs: &'a [PuzzlePack],
+}
+/// check that there are enough rooms in `cave` for each of the `players` and `things` wumpuses, hazards and quivers, which must all start in different rooms
+fn check_space(cave: &Cave, players: usize, things: usize) -> Result<()> {
+    if players.max(1) + things > cave.size() as usize {
+        return Err(anyhow!(
+            "a cave of {} rooms has no space for {} wumpuses, hazards and quivers as well as {}",
+            cave.size(),
+            things,
+            if players > 1 { "the players" } else { "you" }
+        ));
+    }
+    Ok(())
+}
+/** A `Pile` is some arrows lying in a room. */
+#[derive(Debug, Clone, Serialize)]
+pub struct Pile {
+    pub room: u8,
+    pub arrows: i8,
+    /// whether the player knows the arrows are there
+    #[serde(skip)]
+    known: bool,
+    /// whether this is a quiver that was there from the start
+    #[serde(skip)]
+    quiver: bool,
+}
+/** A `Status` is whether the game is still being played, and how it ended. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+pub enum Status {
+    #[default]
+    Playing,
+    Won,
+    Lost(Cause),
+}
+/** A `Cause` is how the player lost. */
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    strum::Display,
+)]
+pub enum Cause {
+    /// fell into a bottomless pit
+    Pit,
+    /// eaten by the wumpus
+    Wumpus,
+    /// shot by their own arrow
+    OwnArrow,
+    /// ran out of arrows
+    OutOfArrows,
+    /// did not kill the wumpuses in time
+    OutOfTime,
+    /// ran out of food
+    Starved,
+    /// shot by another player in a hot-seat game
+    Shot,
+}
+impl Cause {
+    /// how the player lost, for the statistics
+    fn description(self) -> &'static str {
+        match self {
+            Self::Pit => "FELL INTO A PIT",
+            Self::Wumpus => "EATEN BY A WUMPUS",
+            Self::OwnArrow => "SHOT BY YOUR OWN ARROW",
+            Self::OutOfArrows => "RAN OUT OF ARROWS",
+            Self::OutOfTime => "RAN OUT OF TIME",
+            Self::Starved => "STARVED",
+            Self::Shot => "SHOT BY ANOTHER PLAYER",
+        }
+    }
 }
 /** A `Response` is the response of the [Engine] to the webview. */
 #[derive(Debug, Default, Getters, CopyGetters, Clone, Serialize)]
@@ -131,8 +683,107 @@ pub struct Response {
     /// whether the [Engine] and the main program should shut down.
     #[getset(get_copy = "pub")]
     shutdown_required: bool,
+    #[getset(get = "pub")]
     msgs: String,
-    tunnels: [u8; 3],
+    /// the rooms the tunnels from the current room lead to
+    #[getset(get = "pub")]
+    tunnels: Vec<u8>,
+    /// a map of the cave, as SVG
+    map: String,
+    /// the room the player is in
+    #[getset(get_copy = "pub")]
+    room: u8,
+    /// the number of arrows left
+    #[getset(get_copy = "pub")]
+    arrows: i8,
+    /// the number of turns (moves and shots) taken
+    #[getset(get_copy = "pub")]
+    turn: u32,
+    /// whether the game is over
+    #[getset(get_copy = "pub")]
+    status: Status,
+    /// what the player has to do to win
+    #[getset(get_copy = "pub")]
+    goal: Goal,
+    /// the number of wumpuses still alive
+    #[getset(get_copy = "pub")]
+    wumpuses: usize,
+    /// the number of wumpuses killed
+    #[getset(get_copy = "pub")]
+    killed: usize,
+    /// the number of the player whose turn it is, from 1
+    #[getset(get_copy = "pub")]
+    player: usize,
+    /// what everyone can see about each player in a hot-seat game (empty if there is only one player)
+    #[getset(get = "pub")]
+    players: Vec<PlayerPanel>,
+    /// in a hot-seat game, the messages about the turn just played that every player may see
+    #[getset(get = "pub")]
+    public_msgs: String,
+    /// arrows lying in the cave where the player knows they are
+    #[getset(get = "pub")]
+    piles: Vec<Pile>,
+    /// the lamp oil and food the player has left
+    #[getset(get_copy = "pub")]
+    resources: Resources,
+    /// what the player senses in the current room
+    #[getset(get_copy = "pub")]
+    senses: Senses,
+    /// what the player knows about each room (empty for purists)
+    #[getset(get = "pub")]
+    knowledge: Vec<RoomKnowledge>,
+    /// the hint, if one was asked for
+    #[getset(get = "pub")]
+    hint: Option<Hint>,
+    /// the score, once the game has been won
+    #[getset(get_copy = "pub")]
+    score: Option<u32>,
+    /// the table of high scores, if asked for
+    #[getset(get = "pub")]
+    high_scores: Option<Vec<HighScore>>,
+    /// the player's statistics and achievements, if asked for
+    #[getset(get = "pub")]
+    stats: Option<Stats>,
+    /// the state to save when the app is stopped, as (key, value) pairs
+    tag_vec: Vec<(String, String)>,
+    /// the turn being shown, if a recorded game is being replayed
+    replay: Option<ReplayView>,
+    /// the lesson being played, during the tutorial
+    #[getset(get = "pub")]
+    lesson: Option<LessonView>,
+    /// the puzzle being played, if one is
+    #[getset(get = "pub")]
+    puzzle: Option<PuzzleView>,
+    /// the cave being designed, while the cave editor is open
+    #[getset(get = "pub")]
+    editor: Option<EditorView>,
+    /// the rooms the arrow shot this turn went through, from the room it was shot from (empty if none was shot)
+    #[getset(get = "pub")]
+    arrow_path: Vec<u8>,
+    /// the rooms bats carried the player between this turn, from the room with the bats (empty if they did not)
+    #[getset(get = "pub")]
+    bat_path: Vec<u8>,
+    /// the arrow being aimed, while the player is choosing the rooms to shoot it through
+    #[getset(get = "pub")]
+    aim: Option<AimView>,
+}
+impl Response {
+    /// the part of the response that every player in a hot-seat game may see, when it is not for them
+    pub fn public(&self) -> Self {
+        Self {
+            msgs: self.public_msgs.clone(),
+            public_msgs: self.public_msgs.clone(),
+            turn: self.turn,
+            status: self.status,
+            goal: self.goal,
+            wumpuses: self.wumpuses,
+            killed: self.killed,
+            player: self.player,
+            players: self.players.clone(),
+            resources: self.resources,
+            ..Self::default()
+        }
+    }
 }
 impl ResponseTrait for Response {
     fn shutdown_required(&self) -> bool {
@@ -148,73 +799,233 @@ impl fmt::Display for Response {
 /* 0010 */  //  HUNT THE WUMPUS
 /* 0015 */  // :  BY GREGORY YOB
 
-/* 0170 */
-fn rand20() -> u8 {
-    (20.0 * rand::random::<f64>()) as u8 + 1
-}
-/* 0170 */
-fn rand3() -> u8 {
-    (3.0 * rand::random::<f64>()) as u8 + 1
-}
-/* 0170 */
-fn rand4() -> u8 {
-    (4.0 * rand::random::<f64>()) as u8 + 1
-}
 #[derive(Debug, Default)]
 struct Data {
-    s: [[u8; 3]; 20],
-    l: [u8; 6],
-    m: [u8; 6],
+    cave: Cave,
+    /// the rooms of the player and then of each wumpus still alive
+    l: Vec<u8>,
+    m: Vec<u8>,
     ll: u8,
+    /// the number of arrows to start with
+    arrows: i8,
+    /// the pits, bats and any other hazards, and where they are
+    hazards: Vec<Placed>,
+    /// the kind of wumpus to play against
+    wumpus_kind: WumpusKind,
+    /// how each wumpus still alive behaves (in the same order as in `l`)
+    behaviours: Vec<Box<dyn WumpusBehaviour>>,
+    /// the number of wumpuses at the start
+    wumpus_count: usize,
+    /// the number of wumpuses killed
+    killed: usize,
+    goal: Goal,
+    variant: Variant,
+    /// whether arrows that miss can be picked up again
+    arrow_pickup: bool,
+    /// the number of quivers, and the arrows in each
+    quivers: (usize, i8),
+    /// arrows lying in the cave
+    piles: Vec<Pile>,
+    /// the lamp oil and food the player starts with
+    resources: Resources,
+    /// the lamp oil and food the player has left
+    left: Resources,
+    /// the score, once the game has been won
+    score: Option<u32>,
+    high_scores: HighScores,
+    /// what has happened so far in this game
+    tally: GameTally,
+    /// whether this game has been added to the statistics
+    tallied: bool,
+    /// the daily challenge being played, if it is one
+    daily: Option<Daily>,
+    /// what the player has done in this game that changed it, to record with the score of a daily challenge
+    actions: Vec<Action>,
+    /// the number of players taking turns
+    player_count: usize,
+    /// the number of the player whose turn it is, from 1
+    player: usize,
+    /// the number of wumpuses killed by the player whose turn it is
+    player_kills: usize,
+    /// the other players in a hot-seat game, in the order they take their turns
+    waiting: VecDeque<Player>,
+    /// in a hot-seat game, how many of the messages are about the turn just played, for every player to see
+    handover: usize,
+    stats: Stats,
     a: i8,
     f: i8,
+    /// how the player lost, if they did
+    cause: Option<Cause>,
+    /// the number of turns taken
+    turn: u32,
     msgs: Vec<String>,
-    tunnels: [u8; 3],
+    tunnels: Vec<u8>,
+    /// which rooms the player has been in (indexed from 0)
+    visited: Vec<bool>,
+    recording: Recording,
+    /// what the player has found out
+    knowledge: Knowledge,
+    /// what the player senses in the current room
+    senses: Senses,
+    /// whether to hide the knowledge from the player
+    purist: bool,
+    /// the hint for this turn, if the player asked for one
+    hint: Option<Hint>,
+    /// the lesson this game is, during the tutorial
+    lesson: Option<LessonView>,
+    /// the puzzle this game is, if it is one
+    puzzle: Option<PuzzleView>,
+    /// what has happened since the observers were last told
+    events: Vec<GameEvent>,
+    dice: Dice,
 }
 impl Data {
-    pub fn new() -> Self {
+    pub fn new(cave: Cave, config: &Config) -> Self {
         /* 0200 */
         // LOCATE L ARRAY ITEMS
         /* 0210 */  // 1-YOU,2-WUMPUS,3&4-PITS,5&6-BATS
-        let mut new_data = Self{
-            /* 0068 */  //  SET UP CAVE (DODECAHEDRAL NODE LIST)
-            s: /* 0130 */  [ [2,5,8],[1,3,10],[2,4,12],[3,5,14],[1,4,6],[
-                /* 0140 */    5,7,15],[6,8,17],[1,7,9],[8,10,18],[2,9,11], [                /* 0150 */    10,12,19],[3,11,13],[12,14,20],[4,13,15],[6,14,16],[
-                /* 0160 */   15,17,20],[7,16,18],[9,17,19],[11,18,20],[13,16,19]],
-        msgs: vec!["HUNT THE WUMPUS".to_string()],
-        // f: 0,
-        // tunnels: [0; 3],
-        ..Default::default()
-    };
+        let mut new_data = Self {
+            cave,
+            purist: config.purist,
+            arrows: config.arrows,
+            hazards: hazard::classic(config.pits, config.bats),
+            wumpus_kind: config.wumpus,
+            wumpus_count: config.wumpuses,
+            goal: config.goal,
+            arrow_pickup: config.arrow_pickup,
+            quivers: (config.quivers, config.quiver_arrows),
+            resources: config.resources,
+            player_count: config.players,
+            variant: config.variant,
+            dice: Dice::new(config.seed),
+            msgs: vec!["HUNT THE WUMPUS".to_string()],
+            // f: 0,
+            // tunnels: [0; 3],
+            ..Default::default()
+        };
         new_data.renew();
         new_data
     }
     fn renew(&mut self) {
-        let mut good = false;
-        let mut lm = [0u8; 6];
-        while !good {
-            good = true;
-            for lmx in &mut lm {
-                *lmx = rand20();
-            }
-
+        if self.daily.take().is_some() {
+            self.dice = Dice::default();
+        }
+        self.actions.clear();
+        let mut taken: Vec<u8> = vec![];
+        while taken.len() < self.player_count + self.wumpus_count {
+            let room = self.dice.upto(self.cave.size() as usize) as u8;
             /* 0280 */
             // CHECK FOR CROSSOVERS (IE L(1)=L(2),ETC)
-            for i in 0..6 {
-                for j in 0..6 {
-                    if i != j && lm[i] == lm[j] {
-                        good = false;
-                    }
-                }
+            if !taken.contains(&room) {
+                taken.push(room);
+            }
+        }
+        let starts = taken[..self.player_count].to_vec();
+        let mut lm = vec![taken[0]];
+        lm.extend(&taken[self.player_count..]);
+        for placed in &mut self.hazards {
+            placed.rooms = placed.hazard.place(&self.cave, &taken, &mut self.dice);
+            taken.extend(&placed.rooms);
+        }
+        self.piles.clear();
+        while self.piles.len() < self.quivers.0 {
+            let room = self.dice.upto(self.cave.size() as usize) as u8;
+            if !taken.contains(&room) {
+                taken.push(room);
+                self.piles.push(Pile {
+                    room,
+                    arrows: self.quivers.1,
+                    known: false,
+                    quiver: true,
+                });
             }
         }
-        self.l = lm;
-        self.m = lm;
         self.ll = lm[0];
+        self.l = lm.clone();
+        self.m = lm;
         self.f = 0;
+        self.cause = None;
+        self.turn = 0;
+        self.left = self.resources;
+        self.score = None;
+        self.tally = GameTally::default();
+        self.tallied = false;
+        self.visited = vec![false; self.cave.size() as usize];
+        self.visited[self.ll as usize - 1] = true;
         /* 0350 */
         // SET# ARROWS
-        self.a = 5;
+        self.a = self.arrows;
+        self.behaviours = (0..self.wumpus_count)
+            .map(|_| self.wumpus_kind.behaviour())
+            .collect();
+        self.killed = 0;
+        self.knowledge.clear();
+        self.player = 1;
+        self.player_kills = 0;
+        self.waiting = starts[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, room)| {
+                let mut visited = vec![false; self.cave.size() as usize];
+                visited[*room as usize - 1] = true;
+                Player {
+                    number: i + 2,
+                    room: *room,
+                    arrows: self.arrows,
+                    visited,
+                    knowledge: Knowledge::default(),
+                    killed: 0,
+                    out: None,
+                    left: self.resources,
+                }
+            })
+            .collect();
+        self.recording.clear();
+        self.record_start();
+        self.emit(GameEvent::Started { room: self.l[0] });
+    }
+    /// put the player, the wumpuses and the hazards in the rooms given instead of random ones (for the tutorial, puzzles and playtesting), as if the player had already been in the `revealed` rooms. `hazards` has the rooms of each of the game's hazards in turn (the pits and then the bats, in the classic game); any hazards after those go in free rooms at random
+    fn place(&mut self, room: u8, wumpuses: &[u8], hazards: &[&[u8]], revealed: &[u8]) {
+        self.l = vec![room];
+        self.l.extend(wumpuses);
+        self.m = self.l.clone();
+        self.ll = room;
+        let mut taken = self.l.clone();
+        taken.extend(hazards.iter().flat_map(|rooms| rooms.iter()));
+        for (i, placed) in self.hazards.iter_mut().enumerate() {
+            placed.rooms = match hazards.get(i) {
+                Some(rooms) => rooms.to_vec(),
+                None => {
+                    let rooms = placed.hazard.place(&self.cave, &taken, &mut self.dice);
+                    taken.extend(&rooms);
+                    rooms
+                }
+            };
+        }
+        self.visited = vec![false; self.cave.size() as usize];
+        self.knowledge.clear();
+        for seen in revealed {
+            self.visited[*seen as usize - 1] = true;
+            self.knowledge.observe(*seen, self.senses_in(*seen));
+        }
+        self.visited[room as usize - 1] = true;
+        self.recording.clear();
+        self.record_start();
+        // the game set up at random never started
+        self.events.clear();
+        self.emit(GameEvent::Started { room });
+    }
+    /// record the start of the game, with the warnings the player sees
+    fn record_start(&mut self) {
+        let msgs = std::mem::take(&mut self.msgs);
+        self.print_warnings();
+        self.record("START");
+        self.msgs = msgs;
+    }
+    /// record the turn just played so that it can be replayed
+    fn record(&mut self, label: &str) {
+        let frame = Frame::new(self, label);
+        self.recording.push(frame);
     }
 
     fn create_response(&mut self) -> Response {
@@ -234,13 +1045,259 @@ impl Data {
             /* 0550 */
             self.say("HEE HEE HEE - THE WUMPUS'LL GETCHA NEXT TIME!!");
         }
+        let knowledge = self.knowledge();
         Response {
             msgs: self.msgs.join("<br/>"),
-            tunnels: self.tunnels,
+            tunnels: self.tunnels.clone(),
+            map: self.map(&knowledge),
+            room: self.l[0],
+            arrows: self.a,
+            turn: self.turn,
+            status: self.status(),
+            goal: self.goal,
+            wumpuses: self.l.len() - 1,
+            killed: self.killed,
+            player: self.player,
+            players: self.panels(),
+            public_msgs: self.msgs[..self.handover].join("<br/>"),
+            piles: self.piles.iter().filter(|p| p.known).cloned().collect(),
+            resources: self.left,
+            score: self.score,
+            senses: self.senses,
+            knowledge,
+            hint: self.hint.clone(),
+            lesson: self.lesson.clone(),
+            puzzle: self.puzzle.clone(),
+            arrow_path: GameEvent::arrow_path(&self.events),
+            bat_path: GameEvent::bat_path(&self.events),
             ..Response::default()
         }
     }
+    /// whether the game is over
+    fn status(&self) -> Status {
+        match self.f {
+            0 => Status::Playing,
+            f if f > 0 => Status::Won,
+            _ => Status::Lost(self.cause.unwrap_or(Cause::Wumpus)),
+        }
+    }
+    /// the player has lost
+    fn lose(&mut self, cause: Cause) {
+        debug!("lost: {}", cause);
+        self.f = -1;
+        self.cause = Some(cause);
+        self.emit(GameEvent::Lost {
+            player: self.player,
+            cause,
+        });
+    }
+    /// what the player knows about each room, unless they would rather work it out themselves
+    fn knowledge(&self) -> Vec<RoomKnowledge> {
+        if self.purist {
+            vec![]
+        } else {
+            self.knowledge.rooms(
+                &self.cave,
+                self.count(Sense::Draft),
+                self.count(Sense::Bats),
+                self.l.len() - 1,
+            )
+        }
+    }
+    /// work out the chances of where the hazards are and suggest a move
+    fn give_hint(&mut self) {
+        let hint = Hint::new(
+            &self.cave,
+            &self.knowledge,
+            self.l[0],
+            self.count(Sense::Draft),
+            self.count(Sense::Bats),
+            self.l.len() - 1,
+            &mut self.dice,
+        );
+        self.say(&hint.describe());
+        self.hint = Some(hint);
+    }
+    /// draw the map the player sees
+    fn map(&self, knowledge: &[RoomKnowledge]) -> String {
+        let mut map = self.draw(knowledge);
+        if self.f == 0 {
+            map.clickable();
+        }
+        map.render()
+    }
+    /// the map the player sees, with what they know about each room, before it is made clickable
+    fn draw(&self, knowledge: &[RoomKnowledge]) -> Map<'_> {
+        let mut map = Map::new(&self.cave, Some(self.l[0]));
+        for (i, visited) in self.visited.iter().enumerate() {
+            if *visited {
+                map.mark(i as u8 + 1, "visited");
+            }
+        }
+        for (i, room) in knowledge.iter().enumerate() {
+            let room_number = i as u8 + 1;
+            if room.safe {
+                map.mark(room_number, "safe");
+            }
+            for (suspicion, possible, certain) in &[
+                (room.wumpus, "W?", "WUMPUS"),
+                (room.pit, "P?", "PIT"),
+                (room.bats, "B?", "BATS"),
+            ] {
+                match suspicion {
+                    Suspicion::Possible => map.label(room_number, possible),
+                    Suspicion::Certain => map.label(room_number, certain),
+                    _ => {}
+                }
+            }
+        }
+        for pile in self.piles.iter().filter(|pile| pile.known) {
+            if pile.arrows == 1 {
+                map.label(pile.room, "ARROW");
+            } else {
+                map.label(pile.room, &format!("{} ARROWS", pile.arrows));
+            }
+        }
+        if let Some(hint) = &self.hint {
+            for (i, chances) in hint.rooms().iter().enumerate() {
+                for (chance, letter) in &[
+                    (chances.wumpus, "W"),
+                    (chances.pit, "P"),
+                    (chances.bats, "B"),
+                ] {
+                    if *chance > 0.0 {
+                        map.label(i as u8 + 1, &format!("{}{:.0}%", letter, chance * 100.0));
+                    }
+                }
+            }
+        }
+        map
+    }
+    /// start a new game in another cave, if it can be played in, returning whether it could
+    fn change_cave(&mut self, cave: Cave) -> bool {
+        let things = self.wumpus_count
+            + self
+                .hazards
+                .iter()
+                .map(|placed| placed.hazard.count())
+                .sum::<usize>()
+            + self.quivers.0;
+        match cave
+            .check()
+            .and_then(|_| check_space(&cave, self.player_count, things))
+        {
+            Ok(()) => {
+                self.cave = cave;
+                self.renew();
+                true
+            }
+            Err(err) => {
+                self.say(&format!("CANNOT PLAY IN THAT CAVE: {}", err).to_uppercase());
+                false
+            }
+        }
+    }
+    /// start the `daily` challenge: a new game in the cave and with the dice it chooses
+    fn start_daily(&mut self, daily: Daily) {
+        let cave = if self.variant == Variant::WumpusII {
+            Cave::wumpus_ii(daily.cave()).unwrap_or_default()
+        } else {
+            Cave::dodecahedron()
+        };
+        self.daily = None;
+        self.dice = Dice::new(Some(daily.seed));
+        if self.change_cave(cave) {
+            self.say(&format!("DAILY CHALLENGE FOR {}", daily.date));
+            self.daily = Some(daily);
+        }
+    }
     fn show_instructions(&mut self) {
+        if self.variant == Variant::WumpusII {
+            self.show_instructions_ii();
+        } else {
+            self.show_classic_instructions();
+        }
+        let description = self.wumpus_kind.behaviour().description();
+        if !description.is_empty() {
+            self.say(description);
+        }
+        if let Some(goal) = self.describe_goal() {
+            self.say(&goal);
+        }
+        if self.arrow_pickup {
+            self.say("ARROWS THAT MISS CAN BE PICKED UP AGAIN WHERE THEY LAND.");
+        }
+        if self.quivers.0 > 0 {
+            self.say(&format!(
+                "{} ROOMS HAVE A QUIVER OF {} ARROWS IN THEM.",
+                self.quivers.0, self.quivers.1
+            ));
+        }
+        for line in self.resources.describe() {
+            self.say(&line);
+        }
+        if self.player_count > 1 {
+            self.say(&format!(
+                "{} PLAYERS TAKE TURNS IN THE SAME CAVE. THE FIRST TO KILL THE WUMPUS WINS. BEWARE: ARROWS CAN HIT OTHER PLAYERS.",
+                self.player_count
+            ));
+        }
+        if self.lesson.is_none() {
+            self.say("NEW TO THE GAME? THE TUTORIAL TEACHES IT ONE STEP AT A TIME.");
+        }
+    }
+    /// what the player has to do, if it is not just to kill the one wumpus
+    fn describe_goal(&self) -> Option<String> {
+        let wumpuses = if self.wumpus_count == 1 {
+            "THERE IS ONE WUMPUS".to_string()
+        } else {
+            format!("THERE ARE {} WUMPUSES", self.wumpus_count)
+        };
+        match self.goal {
+            Goal::KillAll if self.wumpus_count == 1 => None,
+            Goal::KillAll => Some(format!("{}. YOU MUST GET THEM ALL.", wumpuses)),
+            Goal::KillAny => Some(format!("{}. GET ANY ONE TO WIN.", wumpuses)),
+            Goal::KillAllWithin { turns } => Some(format!(
+                "{}. YOU MUST GET {} WITHIN {} TURNS.",
+                wumpuses,
+                if self.wumpus_count == 1 {
+                    "IT"
+                } else {
+                    "THEM ALL"
+                },
+                turns
+            )),
+        }
+    }
+    /// the instructions for Wumpus II
+    fn show_instructions_ii(&mut self) {
+        let caves = WUMPUS_II_CAVES
+            .iter()
+            .enumerate()
+            .map(|(number, name)| format!("{} - {}<br/>", number, name))
+            .collect::<String>();
+        self.say(&format!(
+            " WELCOME TO 'WUMPUS II'<br/>
+THIS VERSION HAS THE SAME RULES AS 'HUNT THE WUMPUS'.
+HOWEVER, YOU NOW HAVE A CHOICE OF CAVES TO PLAY IN.
+SOME CAVES ARE EASIER THAN OTHERS. ALL CAVES HAVE 20
+ROOMS AND 3 TUNNELS LEADING FROM ONE ROOM TO OTHER ROOMS.<br/>
+THE CAVES ARE:<br/>
+{}<br/>
+IN THE DENDRITE, SOME TUNNELS LEAD TO THE SAME ROOM, SO YOU
+MAY BE WARNED MORE THAN ONCE ABOUT THE SAME HAZARD.<br/>
+IN THE ONE WAY LATTICE, TUNNELS ONLY GO ONE WAY. YOU CAN
+GET BACK TO WHERE YOU WERE, BUT ONLY THE LONG WAY ROUND.
+THE WUMPUS AND YOUR ARROWS ALSO ONLY GO ONE WAY.<br/>
+<br/>
+YOU MAY ALSO DESIGN YOUR OWN CAVE. LIST, FOR EACH ROOM,
+THE ROOMS ITS TUNNELS LEAD TO. EVERY ROOM MUST BE ABLE TO
+REACH EVERY OTHER ROOM, THOUGH NOT NECESSARILY DIRECTLY.<br/>",
+            caves
+        ));
+    }
+    /// the instructions for the classic game
+    fn show_classic_instructions(&mut self) {
         self.say(
             " WELCOME TO 'HUNT THE WUMPUS'<br/>
 THE WUMPUS LIVES IN A CAVE OF 20 ROOMS. EACH ROOM
@@ -304,37 +1361,74 @@ PIT - 'I FEEL A DRAFT'<br/>",
     // /* 1410 */  RETURN
     /* 2000 */  // PRINT LOCATION & HAZARD WARNINGS
     fn print_warnings(&mut self) {
-        /* 2020 */
-        for j in 1..5 {
-            /* 2030 */
-            for k in 0..2 {
-                if self.s[self.l[0] as usize - 1][k] == self.l[j] {
-                    match j - 1 {
-                        /* 2060 */
-                        0 => {
-                            self.say("I SMELL A WUMPUS!");
-                        }
-                        /* 2080 */
-                        1 | 2 => {
-                            self.say("I FEEL A DRAFT");
-                        }
-                        /* 2100 */
-                        3 | 4 => {
-                            self.say("BATS NEARBY!");
-                        }
-                        _ => {} /* 2090 */
-                    }
-                }
-            }
+        if self.left.is_dark() {
+            self.senses = Senses::default();
+            self.say("IT IS TOO DARK TO TELL WHAT IS NEARBY");
+        } else {
+            self.warn();
         }
         /* 2130 */
         self.say(&format!("YOU ARE IN ROOM {}", self.l[0]));
         /* 2140 */
-        let ss = self.s[self.l[0] as usize - 1];
-        self.say(&format!("TUNNELS LEAD TO {}, {}, {}", ss[0], ss[1], ss[2]));
-        self.tunnels = [ss[0], ss[1], ss[2]];
+        let ss = self.cave.tunnels(self.l[0]).to_vec();
+        self.say(&format!(
+            "TUNNELS LEAD TO {}",
+            ss.iter()
+                .map(|room| room.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        self.tunnels = ss;
         /* 2160 */
     }
+    /// warn the player about what they sense in the rooms next to them
+    fn warn(&mut self) {
+        self.senses = self.senses();
+        self.knowledge.observe(self.l[0], self.senses);
+        /* 2060 */
+        if self.wumpus_count == 1 {
+            for _ in 0..self.senses.wumpus {
+                self.say("I SMELL A WUMPUS!");
+            }
+        } else if self.senses.wumpus == 1 {
+            self.say("I SMELL A WUMPUS!");
+        } else if self.senses.wumpus > 1 {
+            self.say(&format!("I SMELL {} WUMPUSES!", self.senses.wumpus));
+        }
+        /* 2080 */
+        let tunnels = self.cave.tunnels(self.l[0]);
+        let mut warnings = vec![];
+        for placed in &self.hazards {
+            for _ in tunnels.iter().filter(|to| placed.rooms.contains(to)) {
+                warnings.push(placed.hazard.warning());
+            }
+        }
+        for warning in warnings {
+            self.say(warning);
+        }
+    }
+    /// count the hazards next to the player
+    fn senses(&self) -> Senses {
+        self.senses_in(self.l[0])
+    }
+    /// count the hazards next to `room`
+    fn senses_in(&self, room: u8) -> Senses {
+        let mut senses = Senses::default();
+        /* 2020 */
+        for to in self.cave.tunnels(room) {
+            /* 2030 */
+            senses.wumpus += self.l[1..].iter().filter(|wumpus| *wumpus == to).count() as u8;
+            for placed in self.hazards.iter().filter(|p| p.rooms.contains(to)) {
+                /* 2050 */
+                match placed.hazard.sense() {
+                    Some(Sense::Draft) => senses.pits += 1,
+                    Some(Sense::Bats) => senses.bats += 1,
+                    None => {}
+                }
+            }
+        }
+        senses
+    }
     // /* 3000 */  // ARROW ROUTINE
     // /* 3010 */  F=0
     // /* 3020 */  // PATH OF ARROW
@@ -351,70 +1445,119 @@ PIT - 'I FEEL A DRAFT'<br/>",
     // /* 3110 */   GOTO 3080
     // /* 3115 COMEHERE */   NEXT K
     /* 3120 */  // SHOOT ARROW
-    fn shoot_arrow(&mut self, p: Vec<u8>) {
+    /// shoot an arrow through the rooms of `p`, returning whether the player had an arrow to shoot
+    fn shoot_arrow(&mut self, p: Vec<u8>) -> bool {
         /* 3130 */
         self.ll = self.l[0];
+        if self.a <= 0 {
+            self.say("YOU HAVE NO ARROWS");
+            return false;
+        }
+        self.tally.shots += 1;
+        let mut flown = vec![];
+        let mut hit = false;
+        let mut diverted = false;
+        let mut stopped = false;
         /* 3140 */
-        for k in 0..p.len() {
+        for room in p {
             let mut arrow_tunnel = false;
             /* 3150 */
-            for k1 in 0..3 {
-                if self.s[self.ll as usize - 1][k1] == p[k] {
+            for k1 in 0..self.cave.tunnels(self.ll).len() {
+                if self.cave.tunnels(self.ll)[k1] == room {
                     arrow_tunnel = true;
                 }
             }
-            if !arrow_tunnel {
+            let from = self.ll;
+            if arrow_tunnel {
+                /* 3295 */
+                self.ll = room;
+            } else {
                 /* 3180 */
                 // NO TUNNEL FOR ARROW
                 /* 3190 */
-                self.ll = self.s[self.ll as usize - 1][rand3() as usize - 1];
+                let tunnels = self.cave.tunnels(self.ll);
+                self.ll = tunnels[self.dice.upto(tunnels.len()) - 1];
+                diverted = true;
                 /* 3200 */
                 // return;
             }
-            /* 3100 */
-            if k > 1 && p[k - 2] == p[k] {
-                /* 3105 */
-                self.say("ARROWS AREN'T THAT CROOKED");
-                return;
+            self.emit(GameEvent::ArrowFlew {
+                from,
+                to: self.ll,
+                diverted: !arrow_tunnel,
+            });
+            hit = self.check_arrow();
+            flown.push(self.ll);
+            if hit {
+                self.tally.kills += 1;
+                self.tally.longest_kill = self.tally.longest_kill.max(flown.len());
             }
-            self.check_arrow(&p, k);
             if self.f != 0 {
-                return;
+                return true;
+            }
+            if hit {
+                break;
+            }
+            if self.arrow_hits_player() {
+                stopped = true;
+                break;
+            }
+            if let Some(msg) = self.arrow_passes(self.ll) {
+                self.say(msg);
+                break;
             }
         }
         /* 3210 */
-        /* 3220 */
-        self.say("MISSED");
+        if !hit && !stopped {
+            /* 3220 */
+            self.say("MISSED");
+        }
+        if self.arrow_pickup {
+            self.drop_arrow(self.ll, !diverted);
+        }
         /* 3225 */
-        self.ll = self.l[1];
+        self.ll = self.l[0];
         /* 3230 */
         // MOVE WUMPUS
         /* 3240 */
-        self.move_wumpus();
+        self.move_wumpus(Noise::Arrow(flown));
         /* 3250 */
         // AMMO CHECK
         /* 3255 */
         self.a -= 1;
         /* 3260 */
-        if self.a <= 0 {
+        if self.a <= 0 && !self.arrows_to_pick_up() {
             /* 3270 */
-            self.f = -1;
+            self.lose(Cause::OutOfArrows);
             /* 3280 */
-            return;
         }
+        true
     }
-    fn check_arrow(&mut self, p: &Vec<u8>, k: usize) {
+    /// whether the arrow hit a wumpus
+    fn check_arrow(&mut self) -> bool {
         /* 3290 */
         // SEE IF ARROW IS AT L(1) OR L(2)
-        /* 3295 COMEHERE */
-        self.ll = p[k];
         /* 3300 COMEHERE */
-        if self.ll == self.l[1] {
+        if let Some(j) = self.l[1..].iter().position(|wumpus| *wumpus == self.ll) {
             /* 3310 */
             self.say("AHA! YOU GOT THE WUMPUS!");
-            /* 3320 */
-            self.f = 1;
-            return;
+            self.emit(GameEvent::WumpusKilled { room: self.ll });
+            self.l.remove(j + 1);
+            self.behaviours.remove(j);
+            self.killed += 1;
+            self.player_kills += 1;
+            self.wumpus_moved();
+            let left = self.l.len() - 1;
+            if self.goal == Goal::KillAny || left == 0 {
+                /* 3320 */
+                self.f = 1;
+                self.emit(GameEvent::Won);
+            } else if left == 1 {
+                self.say("THERE IS STILL ONE WUMPUS LEFT");
+            } else {
+                self.say(&format!("THERE ARE STILL {} WUMPUSES LEFT", left));
+            }
+            return true;
             /* 3330 */
         }
         /* 3340 */
@@ -422,45 +1565,380 @@ PIT - 'I FEEL A DRAFT'<br/>",
         if self.ll == self.l[0] {
             /* 3350 */
             self.say("OUCH! ARROW GOT YOU!");
-            self.f = -1;
-            return;
+            self.lose(Cause::OwnArrow);
             /* 3360 */
         }
+        false
     }
     /* 3370 */
     // MOVE WUMPUS ROUTINE
-    fn move_wumpus(&mut self) {
-        /* 3380 */
-        let k = rand4() as usize;
-        debug!("moving wumpus from {} to #{}", self.l[1], &k);
-        /* 3390 */
-        if k != 4 {
+    fn move_wumpus(&mut self, noise: Noise) {
+        for j in 1..self.l.len() {
+            let at = Surroundings {
+                cave: &self.cave,
+                wumpus: self.l[j],
+                player: self.l[0],
+            };
+            // only the wumpus bumped into is woken by it
+            let heard = match noise {
+                Noise::Bump if self.l[j] != self.l[0] => Noise::Footsteps,
+                _ => noise.clone(),
+            };
+            /* 3380 */
+            let room = match self.behaviours[j - 1].hear(&at, &heard, &mut self.dice) {
+                Some(room) => room,
+                None => continue,
+            };
+            debug!("moving wumpus from {} to {}", self.l[j], room);
+            self.emit(GameEvent::WumpusWoke { room: self.l[j] });
+            if room != self.l[j] {
+                self.emit(GameEvent::WumpusMoved {
+                    from: self.l[j],
+                    to: room,
+                });
+            }
+            self.wumpus_moved();
             /* 3400 */
-            self.l[1] = self.s[self.l[1] as usize - 1][k - 1];
+            self.l[j] = room;
+            /* 3410 */
+            if self.l[j] == self.l[0] {
+                /* 3420 */
+                self.say("TSK TSK TSK- WUMPUS GOT YOU!");
+                /* 3430 */
+                self.lose(Cause::Wumpus);
+                return;
+            }
+            /* 3440 */
+        }
+    }
+    /// leave an arrow lying in `room`
+    fn drop_arrow(&mut self, room: u8, known: bool) {
+        match self
+            .piles
+            .iter_mut()
+            .find(|pile| pile.room == room && !pile.quiver)
+        {
+            Some(pile) => {
+                pile.arrows += 1;
+                pile.known = pile.known || known;
+            }
+            None => self.piles.push(Pile {
+                room,
+                arrows: 1,
+                known,
+                quiver: false,
+            }),
+        }
+    }
+    /// whether there are arrows lying anywhere the player could pick them up: not with a pit, which they would fall into, or with bats, which would carry them off
+    fn arrows_to_pick_up(&self) -> bool {
+        self.piles.iter().any(|pile| {
+            !self
+                .hazards
+                .iter()
+                .any(|placed| placed.hazard.sense().is_some() && placed.rooms.contains(&pile.room))
+        })
+    }
+    /// pick up any arrows in the player's room
+    fn pick_up_arrows(&mut self) {
+        let room = self.l[0];
+        let (found, left): (Vec<Pile>, Vec<Pile>) =
+            self.piles.drain(..).partition(|pile| pile.room == room);
+        self.piles = left;
+        for pile in found {
+            self.a += pile.arrows;
+            self.emit(GameEvent::PickedUp {
+                room,
+                arrows: pile.arrows,
+            });
+            let arrows = if pile.arrows == 1 {
+                "AN ARROW".to_string()
+            } else {
+                format!("{} ARROWS", pile.arrows)
+            };
+            if pile.quiver {
+                self.say(&format!("YOU FOUND A QUIVER WITH {}!", arrows));
+            } else {
+                self.say(&format!("YOU PICKED UP {}", arrows));
+            }
+        }
+    }
+    /// score the game if it has just been won, returning whether the high scores have changed
+    fn finish(&mut self) -> bool {
+        if self.f <= 0 || self.score.is_some() || self.player_count > 1 {
+            return false;
+        }
+        let play = Play {
+            killed: self.killed,
+            turns: self.turn,
+            arrows: self.a,
+            explored: self.visited.iter().filter(|visited| **visited).count(),
+            difficulty: self.difficulty(),
+        };
+        let score = play.score();
+        self.score = Some(score);
+        self.say(&format!("YOUR SCORE IS {}", score));
+        let place = self.high_scores.add(HighScore {
+            score,
+            turns: play.turns,
+            killed: play.killed,
+            explored: play.explored,
+            daily: self.daily.clone(),
+            actions: if self.daily.is_some() {
+                self.actions.clone()
+            } else {
+                vec![]
+            },
+        });
+        match place {
+            Some(0) => self.say("THAT IS A NEW HIGH SCORE!"),
+            Some(place) => self.say(&format!(
+                "THAT IS NUMBER {} IN THE TABLE OF HIGH SCORES",
+                place + 1
+            )),
+            None => {}
+        }
+        place.is_some()
+    }
+    /// add the game to the statistics if it has just finished, returning whether they have changed
+    fn add_to_stats(&mut self) -> bool {
+        if self.f == 0 || self.tallied || self.player_count > 1 {
+            return false;
+        }
+        self.tallied = true;
+        for achievement in self
+            .stats
+            .add(self.status(), self.turn, &self.tally)
+        {
+            self.say(&format!(
+                "ACHIEVEMENT UNLOCKED: {}",
+                achievement.description()
+            ));
+        }
+        true
+    }
+    /// how much harder than the classic game the settings make it, as a percentage
+    fn difficulty(&self) -> u32 {
+        let pits = self.count(Sense::Draft) as i64;
+        let bats = self.count(Sense::Bats) as i64;
+        let mut percent = 100
+            + 15 * (pits - 2)
+            + 10 * (bats - 2)
+            + 15 * (5 - self.arrows as i64)
+            + 50 * (self.wumpus_count as i64 - 1);
+        percent += match self.wumpus_kind {
+            WumpusKind::Classic => 0,
+            WumpusKind::Coward => 10,
+            WumpusKind::Wanderer | WumpusKind::LightSleeper { .. } => 20,
+            WumpusKind::Hunter => 40,
+        };
+        let time_limit = matches!(self.goal, Goal::KillAllWithin { .. });
+        for limited in &[time_limit, self.resources.oil.is_some(), self.resources.food.is_some()] {
+            if *limited {
+                percent += 20;
+            }
+        }
+        if self.arrow_pickup {
+            percent -= 10;
         }
-        /* 3410 */
-        if self.l[1] == self.l[0] {
-            /* 3420 */
-            self.say("TSK TSK TSK- WUMPUS GOT YOU!");
-            /* 3430 */
-            self.f = -1;
+        percent -= 5 * self.quivers.0 as i64;
+        percent.max(25) as u32
+    }
+    /// list the high scores
+    fn show_high_scores(&mut self) {
+        if self.high_scores.scores().is_empty() {
+            self.say("NO HIGH SCORES YET");
+        } else {
+            self.say("HIGH SCORES");
+        }
+    }
+    /// summarise the player's statistics
+    fn show_stats(&mut self) {
+        let stats = self.stats.clone();
+        self.say(&format!(
+            "YOU HAVE PLAYED {} GAMES AND WON {}",
+            stats.games, stats.wins
+        ));
+        for (cause, count) in &stats.losses {
+            self.say(&format!("{}: {}", cause.description(), count));
+        }
+        self.say(&format!(
+            "LONGEST GAME: {} TURNS. ARROWS SHOT: {}. WUMPUSES KILLED: {}. BAT RIDES: {}",
+            stats.longest_survival, stats.shots, stats.kills, stats.bat_rides
+        ));
+        for achievement in &stats.achievements {
+            self.say(&format!("ACHIEVEMENT: {}", achievement.description()));
+        }
+    }
+    /// in a hot-seat game, whether the arrow has hit another player, who is then out
+    fn arrow_hits_player(&mut self) -> bool {
+        let ll = self.ll;
+        match self
+            .waiting
+            .iter_mut()
+            .find(|player| player.out.is_none() && player.room == ll)
+        {
+            Some(player) => {
+                player.out = Some(Cause::Shot);
+                let number = player.number;
+                self.emit(GameEvent::PlayerShot { player: number });
+                self.say(&format!("THE ARROW HIT PLAYER {}!", number));
+                true
+            }
+            None => false,
+        }
+    }
+    /// in a hot-seat game, hand over to the next player still in the game, and tell everyone what happened
+    fn next_player(&mut self) {
+        if self.waiting.is_empty() {
+            return;
+        }
+        self.hand_over();
+        self.handover = self.msgs.len();
+    }
+    /// hand over to the next player still in the game
+    fn hand_over(&mut self) {
+        if self.f > 0 {
+            self.say(&format!("PLAYER {} WINS!", self.player));
+            return;
+        }
+        let out = if self.f < 0 { self.cause } else { None };
+        if out == Some(Cause::OutOfTime) {
+            return;
+        }
+        if out.is_some() {
+            self.say(&format!("PLAYER {} IS OUT", self.player));
+            self.f = 0;
+            self.cause = None;
+        }
+        let number = self.player;
+        let mut this = self.take_player();
+        this.out = out;
+        self.waiting.push_back(this);
+        for _ in 0..self.waiting.len() {
+            let mut next = self.waiting.pop_front().expect("players waiting");
+            if next.out.is_none() && self.l[1..].contains(&next.room) {
+                self.say(&format!("A WUMPUS HAS EATEN PLAYER {}", next.number));
+                next.out = Some(Cause::Wumpus);
+                self.emit(GameEvent::Lost {
+                    player: next.number,
+                    cause: Cause::Wumpus,
+                });
+            }
+            if next.out.is_none() {
+                self.give_turn(next);
+                self.say(&format!("PLAYER {}'S TURN", self.player));
+                return;
+            }
+            self.waiting.push_back(next);
+        }
+        // everyone is out, so the game is over
+        let last = self
+            .waiting
+            .iter()
+            .position(|player| player.number == number)
+            .and_then(|i| self.waiting.remove(i))
+            .expect("player who went out");
+        let cause = last.out.unwrap_or(Cause::Wumpus);
+        self.give_turn(last);
+        // everyone has already been told how the last player went out
+        self.f = -1;
+        self.cause = Some(cause);
+    }
+    /// note that a wumpus may not be where every player thought it was
+    fn wumpus_moved(&mut self) {
+        self.knowledge.wumpus_moved();
+        for player in &mut self.waiting {
+            player.knowledge.wumpus_moved();
+        }
+    }
+    /// take the player whose turn it is out of the game data
+    fn take_player(&mut self) -> Player {
+        Player {
+            number: self.player,
+            room: self.l[0],
+            arrows: self.a,
+            visited: std::mem::take(&mut self.visited),
+            knowledge: std::mem::take(&mut self.knowledge),
+            killed: self.player_kills,
+            out: None,
+            left: self.left,
+        }
+    }
+    /// make it `player`'s turn
+    fn give_turn(&mut self, player: Player) {
+        self.emit(GameEvent::TurnChanged {
+            player: player.number,
+        });
+        self.player = player.number;
+        self.l[0] = player.room;
+        self.ll = player.room;
+        self.a = player.arrows;
+        self.visited = player.visited;
+        self.knowledge = player.knowledge;
+        self.player_kills = player.killed;
+        self.left = player.left;
+    }
+    /// what everyone can see about each player in a hot-seat game
+    fn panels(&self) -> Vec<PlayerPanel> {
+        if self.waiting.is_empty() {
+            return vec![];
+        }
+        let mut panels: Vec<PlayerPanel> = self.waiting.iter().map(Player::panel).collect();
+        panels.push(PlayerPanel {
+            number: self.player,
+            arrows: self.a,
+            killed: self.player_kills,
+            out: self.cause,
+            current: true,
+        });
+        panels.sort_by_key(|panel| panel.number);
+        panels
+    }
+    /// use up the oil for a turn, and food for a move if `moved`; in a hot-seat game each player has their own, and one who runs out goes out of the game
+    fn spend(&mut self, moved: bool) {
+        if self.f != 0 {
+            return;
+        }
+        for shortage in self.left.spend(moved) {
+            self.say(shortage.message());
+            if let Some(cause) = shortage.cause() {
+                if self.f == 0 {
+                    self.lose(cause);
+                }
+            }
+        }
+    }
+    /// the player loses if they have run out of time to kill the wumpuses, and is warned when it is running out
+    fn check_time(&mut self) {
+        if let Goal::KillAllWithin { turns } = self.goal {
+            if self.f == 0 && turns > LOW && self.turn + LOW == turns {
+                self.say("TIME IS RUNNING OUT");
+            }
+            if self.f == 0 && self.turn >= turns {
+                self.say(if self.l.len() > 2 {
+                    "TIME'S UP! THE WUMPUSES HAVE OUTLASTED YOU"
+                } else {
+                    "TIME'S UP! THE WUMPUS HAS OUTLASTED YOU"
+                });
+                self.lose(Cause::OutOfTime);
+            }
         }
-        /* 3440 */
-        debug!("wumpus moved to room {}", self.l[1]);
     }
     /* 4000 */
     //  MOVE ROUTINE
-    fn move_to(&mut self, l: u8) {
+    /// move the player to room `l`, returning whether there was a tunnel to it
+    fn move_to(&mut self, l: u8) -> bool {
         debug!("moving to room {}", l);
         let mut lx = l;
         /* 4010 */
         self.f = 0;
         let mut ok = false;
         /* 4050 */
-        for k in 0..3 {
+        for k in 0..self.cave.tunnels(self.l[0]).len() {
             /* 4060 */
             //  CHECK IF LEGAL MOVE
-            if self.s[self.l[0] as usize - 1][k as usize] == lx {
+            if self.cave.tunnels(self.l[0])[k] == lx {
                 ok = true;
             }
             /* 4080 */
@@ -472,58 +1950,414 @@ PIT - 'I FEEL A DRAFT'<br/>",
                 self.say("NOT POSSIBLE -");
             }
             /* 4110 */
-            return;
+            return false;
         }
+        let mut bumped = false;
+        self.emit(GameEvent::Moved {
+            from: self.l[0],
+            to: lx,
+        });
         loop {
             /* 4120 */
             // CHECK FOR HAZARDS
             /* 4130 */
             self.l[0] = lx;
+            self.visited[lx as usize - 1] = true;
             /* 4140 */
             // WUMPUS
             /* 4150 */
-            if lx == self.l[1] {
+            if self.l[1..].contains(&lx) {
                 /* 4160 */
                 self.say("...OOPS! BUMPED A WUMPUS!");
+                self.emit(GameEvent::Bumped { room: lx });
                 /* 4170 */
                 // MOVE WUMPUS
                 /* 4180 */
-                self.move_wumpus();
+                self.move_wumpus(Noise::Bump);
+                bumped = true;
             }
             /* 4190 */
             if self.f != 0 {
-                return;
+                return true;
             }
             /* 4210 */
             // PIT
-            /* 4220 */
-            if lx == self.l[2] || lx == self.l[3] {
-                /* 4230 */
-                self.say("YYYIIIIEEEE . . . FELL IN PIT");
-                /* 4240 */
-                self.f = -1;
-                /* 4250 */
-                return;
-            }
             /* 4260 */
             // BATS
-            /* 4270 */
-            if lx != self.l[4] && lx != self.l[5] {
-                break;
+            match self.enter(lx) {
+                Effect::Nothing => break,
+                Effect::Lose { cause, msg } => {
+                    /* 4230 */
+                    self.say(msg);
+                    /* 4240 */
+                    self.lose(cause);
+                    /* 4250 */
+                    return true;
+                }
+                Effect::Carry { to, msg } => {
+                    /* 4280 */
+                    self.say(msg);
+                    self.tally.bat_rides += 1;
+                    self.emit(GameEvent::Snatched { from: lx, to });
+                    lx = to;
+                }
             }
-            /* 4280 */
-            self.say("ZAP--SUPER BAT SNATCH! ELSEWHEREVILLE FOR YOU!");
-            /* 4290 */
-            lx = rand20();
             /* 4310 */
         }
+        self.pick_up_arrows();
+        if !bumped {
+            self.move_wumpus(Noise::Footsteps);
+        }
         /* 5000 */
+        true
+    }
+    /// what the hazards in `room` do to the player going into it
+    fn enter(&mut self, room: u8) -> Effect {
+        for placed in self.hazards.iter_mut() {
+            if !placed.rooms.contains(&room) {
+                continue;
+            }
+            let effect = placed.hazard.enter(&self.cave, room, &mut self.dice);
+            if let Effect::Carry { .. } = effect {
+                if placed.hazard.sense() == Some(Sense::Bats) {
+                    self.knowledge.found_bats(room);
+                }
+            }
+            if effect != Effect::Nothing {
+                return effect;
+            }
+        }
+        Effect::Nothing
+    }
+    /// whether the hazards in `room` stop an arrow going through it, and if so what to say
+    fn arrow_passes(&mut self, room: u8) -> Option<&'static str> {
+        let dice = &mut self.dice;
+        self.hazards
+            .iter_mut()
+            .filter(|placed| placed.rooms.contains(&room))
+            .find_map(|placed| placed.hazard.arrow_passes(room, dice))
+    }
+    /// the number of rooms with hazards that the player notices with `sense`
+    fn count(&self, sense: Sense) -> usize {
+        self.hazards
+            .iter()
+            .filter(|placed| placed.hazard.sense() == Some(sense))
+            .map(|placed| placed.hazard.count())
+            .sum()
+    }
+    /// note that something has happened, to tell the observers
+    fn emit(&mut self, event: GameEvent) {
+        debug!("event: {:?}", &event);
+        self.events.push(event);
     }
     fn say(&mut self, msg: &str) {
         debug!("saying: {}", &msg);
         self.msgs.push(msg.to_string());
     }
 }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a new game on a PC, set up as the config `json` says
+    pub(crate) fn game(json: &str) -> Result<Engine> {
+        Engine::new(&Config::from_json(json)?, InterfaceType::PC)
+    }
+    /// a ring of `size` rooms
+    fn ring(size: u8) -> Cave {
+        Cave::new(
+            (1..=size)
+                .map(|room| vec![room % size + 1, (room + size - 2) % size + 1])
+                .collect(),
+        )
+    }
+    #[test]
+    fn cave_too_small_for_the_players_is_refused() -> Result<()> {
+        let mut engine =
+            game(r#"{"variant": "WumpusII", "players": 4, "wumpuses": 1, "pits": 2, "bats": 2}"#)?;
+        let before = engine.data.cave.clone();
+        let response = engine.execute(Action::DesignCave(ring(6)))?;
+        assert!(response.msgs.contains("CANNOT PLAY IN THAT CAVE"));
+        assert_eq!(engine.data.cave, before);
+        let response = engine.execute(Action::DesignCave(ring(9)))?;
+        assert!(!response.msgs.contains("CANNOT PLAY IN THAT CAVE"));
+        assert_eq!(engine.data.cave, ring(9));
+        Ok(())
+    }
+    #[test]
+    fn replay_only_once_the_game_is_over() -> Result<()> {
+        let mut engine = game(r#"{"arrows": 1}"#)?;
+        let response = engine.execute(Action::Replay)?;
+        assert!(response.replay.is_none());
+        assert!(response.msgs.contains("ONCE IT IS OVER"));
+        let room = engine.data.cave.tunnels(engine.data.l[0])[0];
+        let response = engine.execute(Action::Shoot(vec![room]))?;
+        assert_ne!(response.status, Status::Playing);
+        assert!(engine.execute(Action::Replay)?.replay.is_some());
+        Ok(())
+    }
+    #[test]
+    fn hot_seat_players_have_their_own_resources() -> Result<()> {
+        let mut engine =
+            game(r#"{"players": 2, "pits": 0, "bats": 0, "resources": {"oil": 5, "food": 1}}"#)?;
+        let response = engine.execute(Action::Pass)?;
+        assert_eq!(response.player, 2);
+        assert_eq!(response.resources.oil, Some(5));
+        let response = engine.execute(Action::Pass)?;
+        assert_eq!(response.player, 1);
+        assert_eq!(response.resources.oil, Some(4));
+        // player 1 moves somewhere safe, and starves
+        let data = &engine.data;
+        let room = *data
+            .cave
+            .tunnels(data.l[0])
+            .iter()
+            .find(|room| !data.l[1..].contains(room))
+            .expect("a room without a wumpus");
+        let response = engine.execute(Action::Move(room))?;
+        assert!(response.msgs.contains("PLAYER 1 IS OUT"));
+        assert_eq!(response.status, Status::Playing);
+        assert_eq!(response.player, 2);
+        assert_eq!(response.resources.food, Some(1));
+        assert_eq!(response.resources.oil, Some(4));
+        assert_eq!(response.players[0].out, Some(Cause::Starved));
+        Ok(())
+    }
+    #[test]
+    fn impossible_move_keeps_the_turn() -> Result<()> {
+        let mut engine = game(r#"{"players": 2}"#)?;
+        let response = engine.execute(Action::Move(99))?;
+        assert!(response.msgs.contains("NOT POSSIBLE"));
+        assert_eq!(response.player, 1);
+        assert_eq!(response.turn, 0);
+        let response = engine.execute(Action::Pass)?;
+        assert_eq!(response.player, 2);
+        assert_eq!(response.turn, 1);
+        Ok(())
+    }
+    #[test]
+    fn arrows_in_a_pit_are_lost() -> Result<()> {
+        let mut engine = game(r#"{"arrows": 1, "arrow_pickup": true}"#)?;
+        engine.data.place(1, &[20], &[&[2, 15], &[18, 19]], &[]);
+        let response = engine.execute(Action::Shoot(vec![2]))?;
+        assert!(response.msgs.contains("MISSED"));
+        assert_eq!(response.status, Status::Lost(Cause::OutOfArrows));
+        Ok(())
+    }
+    #[test]
+    fn one_of_two_wumpuses_wins_only_if_any_will_do() -> Result<()> {
+        for (goal, status) in [("KillAll", Status::Playing), ("KillAny", Status::Won)] {
+            let mut engine = game(&format!(
+                r#"{{"wumpuses": 2, "pits": 0, "bats": 0, "goal": "{}"}}"#,
+                goal
+            ))?;
+            engine.data.place(1, &[2, 20], &[], &[]);
+            let response = engine.execute(Action::Shoot(vec![2]))?;
+            assert!(response.msgs.contains("AHA! YOU GOT THE WUMPUS!"));
+            assert_eq!(response.status, status, "{}", goal);
+            assert_eq!(engine.data.l.len(), 2);
+        }
+        Ok(())
+    }
+    #[test]
+    fn daily_score_is_verified_by_playing_it_again() -> Result<()> {
+        let mut engine = game("{}")?;
+        engine.execute(Action::StartDaily)?;
+        // shoot straight at the wumpus, which is never more than 5 rooms away in the dodecahedron
+        let distances = engine.data.cave.distances(engine.data.l[1]);
+        let mut path = vec![engine.data.l[0]];
+        while let Some(&room) = path.last().filter(|room| **room != engine.data.l[1]) {
+            let nearer = engine
+                .data
+                .cave
+                .tunnels(room)
+                .iter()
+                .find(|to| distances[**to as usize - 1] < distances[room as usize - 1]);
+            path.extend(nearer);
+        }
+        path.remove(0);
+        let response = engine.execute(Action::Shoot(path))?;
+        assert_eq!(response.status, Status::Won);
+
+        let config = Config::from_json("{}")?;
+        let entry = engine.data.high_scores.scores()[0].clone();
+        assert!(Engine::verify(&config, &entry)?);
+        let mut tampered = entry.clone();
+        tampered.score += 1;
+        assert!(!Engine::verify(&config, &tampered)?);
+        let mut tampered = entry.clone();
+        tampered.actions.insert(0, Action::Pass);
+        assert!(!Engine::verify(&config, &tampered)?);
+        let mut tampered = entry;
+        tampered.daily = None;
+        assert!(Engine::verify(&config, &tampered).is_err());
+        Ok(())
+    }
+    #[test]
+    fn refused_actions_use_up_nothing() -> Result<()> {
+        let mut engine = game(
+            r#"{"arrows": 1, "arrow_pickup": true, "pits": 0, "bats": 0, "seed": 7,
+                "resources": {"oil": 5, "food": 5}, "goal": {"KillAllWithin": {"turns": 9}}}"#,
+        )?;
+        let response = engine.execute(Action::Move(99))?;
+        assert_eq!(response.turn, 0);
+        assert_eq!(response.resources.oil, Some(5));
+        assert_eq!(response.resources.food, Some(5));
+        let data = &engine.data;
+        let room = *data
+            .cave
+            .tunnels(data.l[0])
+            .iter()
+            .find(|room| !data.l[1..].contains(room))
+            .expect("a room without a wumpus");
+        let response = engine.execute(Action::Shoot(vec![room]))?;
+        assert_eq!(response.status, Status::Playing);
+        assert_eq!(response.turn, 1);
+        assert_eq!(response.resources.oil, Some(4));
+        let response = engine.execute(Action::Shoot(vec![room]))?;
+        assert!(response.msgs.contains("YOU HAVE NO ARROWS"));
+        assert_eq!(response.turn, 1);
+        assert_eq!(response.resources.oil, Some(4));
+        assert_eq!(response.resources.food, Some(5));
+        Ok(())
+    }
+    #[test]
+    fn crooked_shot_is_refused_before_it_flies() -> Result<()> {
+        let mut engine = game("{}")?;
+        let next = engine.data.cave.tunnels(engine.data.l[0])[0];
+        let back = engine.data.l[0];
+        let response = engine.execute(Action::Shoot(vec![next, back, next]))?;
+        assert!(response.msgs.contains("ARROWS AREN'T THAT CROOKED"));
+        assert_eq!(response.turn, 0);
+        assert_eq!(response.arrows, 5);
+        assert!(response.arrow_path.is_empty());
+        Ok(())
+    }
+    #[test]
+    fn missed_arrows_can_be_picked_up() -> Result<()> {
+        let mut engine = game(
+            r#"{"arrows": 1, "arrow_pickup": true, "quivers": 1, "pits": 0, "bats": 0, "seed": 7}"#,
+        )?;
+        let quivers: Vec<&Pile> = engine
+            .data
+            .piles
+            .iter()
+            .filter(|pile| pile.quiver)
+            .collect();
+        assert_eq!(quivers.len(), 1);
+        assert_eq!(quivers[0].arrows, 5);
+        assert!(!quivers[0].known);
+        let data = &engine.data;
+        let room = *data
+            .cave
+            .tunnels(data.l[0])
+            .iter()
+            .find(|room| {
+                !data.l[1..].contains(room) && !data.piles.iter().any(|pile| pile.room == **room)
+            })
+            .expect("an empty room");
+        // the last arrow misses, but can still be picked up, so the game goes on
+        let response = engine.execute(Action::Shoot(vec![room]))?;
+        assert!(response.msgs.contains("MISSED"));
+        assert_eq!(response.status, Status::Playing);
+        assert_eq!(response.arrows, 0);
+        assert_eq!(response.piles().len(), 1);
+        let response = engine.execute(Action::Move(room))?;
+        assert!(response.msgs.contains("YOU PICKED UP AN ARROW"));
+        assert_eq!(response.arrows, 1);
+        assert!(response.piles().is_empty());
+        Ok(())
+    }
+    /** `Quicksand` is a hazard that no sense notices. */
+    #[derive(Debug)]
+    struct Quicksand;
+    impl hazard::Hazard for Quicksand {
+        fn count(&self) -> usize {
+            2
+        }
+        fn label(&self) -> &'static str {
+            "QUICKSAND"
+        }
+        fn class(&self) -> &'static str {
+            "quicksand"
+        }
+        fn warning(&self) -> &'static str {
+            "THE FLOOR IS SOFT"
+        }
+        fn enter(&mut self, _cave: &Cave, _room: u8, _dice: &mut Dice) -> Effect {
+            Effect::Nothing
+        }
+    }
+    #[test]
+    fn hazards_are_placed_whatever_their_sense() {
+        let mut data = Data::new(Cave::dodecahedron(), &Config::default());
+        data.hazards.push(Placed {
+            hazard: Box::new(Quicksand),
+            rooms: vec![],
+        });
+        data.place(1, &[2], &[&[3, 4], &[5], &[6, 7]], &[]);
+        let rooms: Vec<&[u8]> = data
+            .hazards
+            .iter()
+            .map(|placed| &placed.rooms[..])
+            .collect();
+        assert_eq!(rooms, [&[3, 4][..], &[5], &[6, 7]]);
+
+        // a hazard with no rooms given goes in free rooms
+        data.place(1, &[2], &[&[3, 4], &[5]], &[]);
+        let quicksand = &data.hazards[2].rooms;
+        assert_eq!(quicksand.len(), 2);
+        assert!(quicksand.iter().all(|room| *room > 5));
+        assert_ne!(quicksand[0], quicksand[1]);
+    }
+    #[test]
+    fn game_lost_by_passing_is_counted() -> Result<()> {
+        let mut engine = game(r#"{"goal": {"KillAllWithin": {"turns": 1}}}"#)?;
+        let response = engine.execute(Action::Pass)?;
+        assert_eq!(response.status, Status::Lost(Cause::OutOfTime));
+        assert_eq!(engine.data.stats.games, 1);
+        assert_eq!(engine.data.stats.losses.get(&Cause::OutOfTime), Some(&1));
+        Ok(())
+    }
+    #[test]
+    fn wanderer_moves_while_the_player_passes() -> Result<()> {
+        let mut engine = game(r#"{"wumpus": "Wanderer", "pits": 0, "bats": 0, "seed": 3}"#)?;
+        let start = engine.data.l[1];
+        for _ in 0..10 {
+            engine.execute(Action::Pass)?;
+            if engine.data.l[1] != start || engine.data.f != 0 {
+                return Ok(());
+            }
+        }
+        panic!("the wumpus stayed in room {} for 10 turns", start);
+    }
+    #[test]
+    fn time_runs_out_once() -> Result<()> {
+        let mut engine = game(r#"{"goal": {"KillAllWithin": {"turns": 4}}}"#)?;
+        let mut msgs = vec![];
+        for _ in 0..4 {
+            msgs.push(engine.execute(Action::Pass)?.msgs);
+        }
+        assert!(msgs[0].contains("TIME IS RUNNING OUT"));
+        let msgs = msgs.join("<br/>");
+        assert_eq!(msgs.matches("TIME IS RUNNING OUT").count(), 1);
+        assert_eq!(msgs.matches("TIME'S UP").count(), 1);
+        assert_eq!(engine.data.status(), Status::Lost(Cause::OutOfTime));
+        Ok(())
+    }
+    #[test]
+    fn config_too_big_for_the_cave_is_refused() -> Result<()> {
+        let config = Config {
+            cave: Some(ring(6)),
+            players: 4,
+            pits: 2,
+            bats: 0,
+            ..Config::default()
+        };
+        assert!(Engine::new(&config, InterfaceType::PC).is_err());
+        Ok(())
+    }
+}
 /* This Source Code Form is subject to the terms of the Mozilla Public
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
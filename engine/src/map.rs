@@ -0,0 +1,212 @@
+/*! drawing a map of the cave as SVG */
+
+use crate::cave::Cave;
+use std::fmt::Write;
+
+/** A `Map` is a drawing of a [Cave], with some rooms highlighted. */
+#[derive(Debug)]
+pub struct Map<'a> {
+    cave: &'a Cave,
+    /// the room the player is in, if any
+    current: Option<u8>,
+    /// CSS classes for each room
+    classes: Vec<Vec<&'static str>>,
+    /// extra text shown under each room number
+    labels: Vec<String>,
+    /// whether clicking on a room next to the current room moves there
+    clickable: bool,
+    /// the JavaScript function to call with the number of any room clicked on, instead
+    select: Option<&'static str>,
+    /// the rooms an arrow being aimed can be aimed at next, if one is being aimed; clicking on one of them aims it there, instead of moving
+    targets: Option<Vec<u8>>,
+}
+impl<'a> Map<'a> {
+    /// create a map of `cave`
+    pub fn new(cave: &'a Cave, current: Option<u8>) -> Self {
+        let size = cave.size() as usize;
+        Self {
+            cave,
+            current,
+            classes: vec![vec![]; size],
+            labels: vec![String::new(); size],
+            clickable: false,
+            select: None,
+            targets: None,
+        }
+    }
+    /// add a CSS class to a room
+    pub fn mark(&mut self, room: u8, class: &'static str) {
+        self.classes[room as usize - 1].push(class);
+    }
+    /// add some text to a room
+    pub fn label(&mut self, room: u8, text: &str) {
+        let label = &mut self.labels[room as usize - 1];
+        if !label.is_empty() {
+            label.push(' ');
+        }
+        label.push_str(text);
+    }
+    /// allow the player to move by clicking on the map
+    pub fn clickable(&mut self) {
+        self.clickable = true;
+    }
+    /// call the JavaScript function `function` with the number of any room clicked on
+    pub fn selectable(&mut self, function: &'static str) {
+        self.select = Some(function);
+    }
+    /// show the rooms an arrow is being aimed through, numbered, and let the player aim it at one of the rooms in `next` by clicking on it
+    pub fn aim(&mut self, path: &[u8], next: &[u8]) {
+        for (i, room) in path.iter().enumerate() {
+            self.mark(*room, "aimed");
+            self.label(*room, &format!("#{}", i + 1));
+        }
+        for room in next {
+            self.mark(*room, "target");
+        }
+        self.targets = Some(next.to_vec());
+    }
+    /// draw the map
+    pub fn render(&self) -> String {
+        let layout = self.cave.layout();
+        let mut svg = String::new();
+        // a map with rooms to click on is a group of buttons; otherwise it is just a picture
+        let _ = write!(
+            svg,
+            r#"<svg class="map" role="{}" aria-label="map of the cave" viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg">"#,
+            if self.clickable || self.select.is_some() || self.targets.is_some() {
+                "group"
+            } else {
+                "img"
+            }
+        );
+        svg.push_str(r#"<defs><marker id="oneway" viewBox="0 0 10 10" refX="26" refY="5" markerUnits="userSpaceOnUse" markerWidth="2.5" markerHeight="2.5" orient="auto"><path d="M0,0 L10,5 L0,10 z"/></marker></defs>"#);
+        for from in 1..=self.cave.size() {
+            let tunnels = self.cave.tunnels(from);
+            for (i, to) in tunnels.iter().enumerate() {
+                let both_ways = self.cave.is_tunnel(*to, from);
+                // a tunnel both ways is drawn once, from its lower room, and a tunnel listed twice (as in a degenerate cave) only the first time
+                if (both_ways && *to < from) || tunnels[..i].contains(to) {
+                    continue;
+                }
+                let adjacent =
+                    self.current == Some(from) || (both_ways && self.current == Some(*to));
+                let (x1, y1) = layout[from as usize - 1];
+                let (x2, y2) = layout[*to as usize - 1];
+                let _ = write!(
+                    svg,
+                    r#"<line class="tunnel{}{}" x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}"{}/>"#,
+                    if adjacent { " adjacent" } else { "" },
+                    if both_ways { "" } else { " oneway" },
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    if both_ways {
+                        ""
+                    } else {
+                        r#" marker-end="url(#oneway)""#
+                    }
+                );
+            }
+        }
+        for room in 1..=self.cave.size() {
+            let (x, y) = layout[room as usize - 1];
+            let mut classes = vec!["room"];
+            classes.extend(self.classes[room as usize - 1].iter());
+            let next = self
+                .current
+                .is_some_and(|current| self.cave.is_tunnel(current, room));
+            if self.current == Some(room) {
+                classes.push("current");
+            } else if next {
+                classes.push("next");
+            }
+            let label = &self.labels[room as usize - 1];
+            let onclick = if let Some(function) = self.select {
+                let name = format!("room {} {}", room, label.to_lowercase());
+                format!(
+                    r#" onclick="{}({})" tabindex="0" role="button" aria-label="{}""#,
+                    function,
+                    room,
+                    name.trim_end()
+                )
+            } else if let Some(targets) = &self.targets {
+                if targets.contains(&room) {
+                    format!(
+                        r#" onclick="aimAt({})" tabindex="0" role="button" aria-label="shoot through room {}""#,
+                        room, room
+                    )
+                } else {
+                    String::new()
+                }
+            } else if self.clickable && next {
+                format!(
+                    r#" onclick="invoke({{Move: {}}})" tabindex="0" role="button" aria-label="move to room {}""#,
+                    room, room
+                )
+            } else {
+                String::new()
+            };
+            let _ = write!(
+                svg,
+                r#"<g class="{}" data-room="{}"{}><circle cx="{:.1}" cy="{:.1}" r="4"/><text x="{:.1}" y="{:.1}">{}</text>"#,
+                classes.join(" "),
+                room,
+                onclick,
+                x,
+                y,
+                x,
+                y + 1.2,
+                room
+            );
+            if !label.is_empty() {
+                let _ = write!(
+                    svg,
+                    r#"<text class="label" x="{:.1}" y="{:.1}">{}</text>"#,
+                    x,
+                    y + 6.5,
+                    label
+                );
+            }
+            svg.push_str("</g>");
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn every_room_and_tunnel_is_drawn() {
+        let caves = std::iter::once(Cave::dodecahedron())
+            .chain((0..6).filter_map(Cave::wumpus_ii))
+            .collect::<Vec<_>>();
+        assert_eq!(caves.len(), 7);
+        for cave in &caves {
+            let svg = Map::new(cave, Some(1)).render();
+            for room in 1..=cave.size() {
+                let node = format!(r#"data-room="{}""#, room);
+                assert_eq!(svg.matches(&node).count(), 1, "room {}", room);
+            }
+            // a tunnel both ways is one line, and a one-way tunnel a line with an arrowhead
+            let tunnels = (1..=cave.size())
+                .flat_map(|from| {
+                    cave.tunnels(from)
+                        .iter()
+                        .map(move |to| (from.min(*to), from.max(*to)))
+                })
+                .collect::<BTreeSet<_>>();
+            assert_eq!(svg.matches("<line ").count(), tunnels.len());
+        }
+        assert_eq!(
+            Map::new(&caves[0], None).render().matches("<line ").count(),
+            30
+        );
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,158 @@
+/*! puzzles: hand-made positions to be solved within a number of moves, collected in packs */
+
+use crate::{Cave, Config, Data, Goal, WUMPUS_II_CAVES};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// the packs of puzzles that come with the game
+const SHIPPED: [&str; 1] = [include_str!("../puzzles/first-steps.json")];
+
+/** A `Puzzle` is a game set up by hand: where the player, the wumpuses and the hazards are, what the player already knows, and how many moves they have to kill every wumpus in. */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Puzzle {
+    pub name: String,
+    /// what the player is told at the start
+    #[serde(default)]
+    pub description: String,
+    /// which of the caves of Wumpus II to play in (see [WUMPUS_II_CAVES]); 0, the classic cave, if not given
+    #[serde(default)]
+    pub cave: u8,
+    /// the room the player starts in
+    pub room: u8,
+    pub wumpuses: Vec<u8>,
+    #[serde(default)]
+    pub pits: Vec<u8>,
+    #[serde(default)]
+    pub bats: Vec<u8>,
+    pub arrows: i8,
+    /// rooms the player has already been in, so that they know what can be sensed there
+    #[serde(default)]
+    pub visited: Vec<u8>,
+    /// the number of moves and shots allowed
+    pub moves: u32,
+    /// the seed for the dice, so that the wumpuses and bats do the same every time
+    #[serde(default)]
+    pub seed: u64,
+}
+impl Puzzle {
+    /// the cave the puzzle is played in
+    fn the_cave(&self) -> Result<Cave> {
+        Cave::wumpus_ii(self.cave).ok_or_else(|| {
+            anyhow!(
+                "there is no cave {} (there are {})",
+                self.cave,
+                WUMPUS_II_CAVES.len()
+            )
+        })
+    }
+    /// check that the puzzle can be played
+    pub fn check(&self) -> Result<()> {
+        let cave = self.the_cave()?;
+        let mut taken = vec![self.room];
+        for room in self.wumpuses.iter().chain(&self.pits).chain(&self.bats) {
+            if taken.contains(room) {
+                return Err(anyhow!("{}: room {} is used twice", self.name, room));
+            }
+            taken.push(*room);
+        }
+        for room in taken.iter().chain(&self.visited) {
+            if *room < 1 || *room > cave.size() {
+                return Err(anyhow!("{}: there is no room {}", self.name, room));
+            }
+        }
+        if let Some(room) = self.visited.iter().find(|room| taken[1..].contains(room)) {
+            return Err(anyhow!(
+                "{}: the player cannot have been in room {}, as it is not safe",
+                self.name,
+                room
+            ));
+        }
+        if self.wumpuses.is_empty() {
+            return Err(anyhow!("{}: there must be at least one wumpus", self.name));
+        }
+        if self.arrows < 1 || self.moves < 1 {
+            return Err(anyhow!(
+                "{}: the player needs at least one arrow and one move",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+    /// set up the game, with the description to show
+    pub(crate) fn data(&self, view: PuzzleView) -> Result<Data> {
+        let config = Config {
+            arrows: self.arrows,
+            pits: self.pits.len(),
+            bats: self.bats.len(),
+            wumpuses: self.wumpuses.len(),
+            goal: Goal::KillAllWithin { turns: self.moves },
+            seed: Some(self.seed),
+            ..Config::default()
+        };
+        let mut data = Data::new(self.the_cave()?, &config);
+        data.place(
+            self.room,
+            &self.wumpuses,
+            &[&self.pits, &self.bats],
+            &self.visited,
+        );
+        data.msgs = vec![format!("PUZZLE: {}", self.name.to_uppercase())];
+        if !self.description.is_empty() {
+            data.say(&self.description.to_uppercase());
+        }
+        data.say(&format!(
+            "KILL {} IN {} {}.",
+            if self.wumpuses.len() == 1 {
+                "THE WUMPUS".to_string()
+            } else {
+                format!("ALL {} WUMPUSES", self.wumpuses.len())
+            },
+            self.moves,
+            if self.moves == 1 { "MOVE" } else { "MOVES" }
+        ));
+        data.puzzle = Some(view);
+        Ok(data)
+    }
+}
+/** A `PuzzlePack` is a set of [Puzzle]s, kept as JSON. */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PuzzlePack {
+    pub name: String,
+    pub puzzles: Vec<Puzzle>,
+}
+impl PuzzlePack {
+    /// read a pack from JSON, checking that every puzzle in it can be played
+    pub fn from_json(json: &str) -> Result<Self> {
+        let pack: Self = serde_json::from_str(json)?;
+        for puzzle in &pack.puzzles {
+            puzzle.check()?;
+        }
+        Ok(pack)
+    }
+    /// read a pack from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow!("puzzle pack {}: {}", path.display(), err))
+    }
+    /// the packs that come with the game
+    pub fn shipped() -> Result<Vec<Self>> {
+        SHIPPED.iter().map(|json| Self::from_json(json)).collect()
+    }
+}
+/** A `PuzzleView` is sent to the webview while a puzzle is being played. */
+#[derive(Debug, Clone, Serialize)]
+pub struct PuzzleView {
+    /// the number of the pack (from 0)
+    pub pack: usize,
+    /// the number of the puzzle in the pack (from 0)
+    pub number: usize,
+    pub name: String,
+    /// the number of moves and shots allowed
+    pub moves: u32,
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,132 @@
+/*! recording of a game so that it can be replayed turn by turn */
+
+use crate::{hazard::Placed, map::Map, Cave, Data, Response};
+use log::debug;
+use serde::Serialize;
+
+/** A `Frame` is the state of the game after one turn. */
+#[derive(Debug, Clone, Serialize)]
+pub struct Frame {
+    /// what the player did on this turn
+    label: String,
+    /// the room the player is in
+    player: u8,
+    /// the rooms the wumpuses are in
+    wumpuses: Vec<u8>,
+    /// the rooms with each hazard
+    hazards: Vec<HazardRooms>,
+    /// the messages shown to the player on this turn
+    msgs: String,
+}
+impl Frame {
+    /// capture the state of the game
+    pub fn new(data: &Data, label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            player: data.l[0],
+            wumpuses: data.l[1..].to_vec(),
+            hazards: HazardRooms::all(&data.hazards),
+            msgs: data.msgs.join("<br/>"),
+        }
+    }
+    /// draw the map showing where everything is
+    fn map(&self, cave: &Cave) -> String {
+        let mut map = Map::new(cave, Some(self.player));
+        for wumpus in &self.wumpuses {
+            map.label(*wumpus, "WUMPUS");
+            map.mark(*wumpus, "wumpus");
+        }
+        for hazard in &self.hazards {
+            hazard.draw(&mut map);
+        }
+        map.render()
+    }
+}
+/** `HazardRooms` are where one kind of hazard is. */
+#[derive(Debug, Clone, Serialize)]
+pub struct HazardRooms {
+    label: &'static str,
+    class: &'static str,
+    rooms: Vec<u8>,
+}
+impl HazardRooms {
+    /// where each kind of hazard is
+    pub fn all(hazards: &[Placed]) -> Vec<Self> {
+        hazards
+            .iter()
+            .map(|placed| Self {
+                label: placed.hazard.label(),
+                class: placed.hazard.class(),
+                rooms: placed.rooms.clone(),
+            })
+            .collect()
+    }
+    /// mark the rooms with this hazard on `map`
+    pub fn draw(&self, map: &mut Map) {
+        for room in &self.rooms {
+            map.label(*room, self.label);
+            map.mark(*room, self.class);
+        }
+    }
+}
+/** A `ReplayView` is sent to the webview when a recorded game is being replayed. */
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayView {
+    /// the number of the turn being shown (the start of the game is turn 0)
+    turn: usize,
+    /// the number of turns recorded
+    turns: usize,
+    frame: Frame,
+}
+/** A `Recording` holds the [Frame]s of the current game, and the turn being shown when the game is replayed. */
+#[derive(Debug, Default)]
+pub struct Recording {
+    frames: Vec<Frame>,
+    position: usize,
+}
+impl Recording {
+    /// forget the previous game
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.position = 0;
+    }
+    /// add a turn to the recording
+    pub fn push(&mut self, frame: Frame) {
+        debug!("recording turn {} ({})", self.frames.len(), &frame.label);
+        self.frames.push(frame);
+    }
+    /// start replaying from the beginning of the game
+    pub fn start(&mut self, cave: &Cave) -> Response {
+        self.position = 0;
+        self.response(cave)
+    }
+    /// move forwards (or backwards if `delta` is negative) through the game
+    pub fn step(&mut self, cave: &Cave, delta: isize) -> Response {
+        let last = self.frames.len().saturating_sub(1) as isize;
+        self.position = (self.position as isize + delta).max(0).min(last) as usize;
+        self.response(cave)
+    }
+    /// the response showing the current turn of the replay
+    fn response(&self, cave: &Cave) -> Response {
+        let turn = self.position;
+        match self.frames.get(turn) {
+            None => Response {
+                msgs: "NOTHING TO REPLAY".to_string(),
+                ..Response::default()
+            },
+            Some(frame) => Response {
+                msgs: frame.msgs.clone(),
+                map: frame.map(cave),
+                replay: Some(ReplayView {
+                    turn,
+                    turns: self.frames.len() - 1,
+                    frame: frame.clone(),
+                }),
+                ..Response::default()
+            },
+        }
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
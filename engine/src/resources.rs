@@ -0,0 +1,133 @@
+/*! resources that run out: lamp oil and food (the time the player has is set by the [Goal](crate::Goal)) */
+
+use crate::Cause;
+use serde::{Deserialize, Serialize};
+
+/// how low a resource (or the time left) gets before the player is warned
+pub const LOW: u32 = 3;
+
+/** `Resources` limit how long the player can keep going. Each is unlimited if not given. */
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Resources {
+    /// the number of turns the lamp burns for. In the dark the player cannot sense what is in the rooms next to them.
+    pub oil: Option<u32>,
+    /// the number of moves the player can make before they starve
+    pub food: Option<u32>,
+}
+/** A `Shortage` is a resource that has just run low or run out. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shortage {
+    OilLow,
+    OilOut,
+    FoodLow,
+    Starving,
+}
+impl Shortage {
+    /// what the player is told
+    pub fn message(self) -> &'static str {
+        match self {
+            Self::OilLow => "YOUR LAMP IS FLICKERING",
+            Self::OilOut => "YOUR LAMP HAS RUN OUT OF OIL",
+            Self::FoodLow => "YOU ARE GETTING HUNGRY",
+            Self::Starving => "YOU HAVE RUN OUT OF FOOD AND STARVE",
+        }
+    }
+    /// how the player loses, if they do
+    pub fn cause(self) -> Option<Cause> {
+        match self {
+            Self::Starving => Some(Cause::Starved),
+            _ => None,
+        }
+    }
+}
+impl Resources {
+    /// whether the lamp has gone out
+    pub fn is_dark(&self) -> bool {
+        self.oil == Some(0)
+    }
+    /// use up one turn's worth, and the food for a move if `moved`, returning what has run low or out
+    pub fn spend(&mut self, moved: bool) -> Vec<Shortage> {
+        let mut shortages = vec![];
+        let mut use_one = |left: &mut Option<u32>, low: Shortage, out: Shortage| {
+            if let Some(left) = left {
+                *left = left.saturating_sub(1);
+                if *left == 0 {
+                    shortages.push(out);
+                } else if *left == LOW {
+                    shortages.push(low);
+                }
+            }
+        };
+        if !self.is_dark() {
+            use_one(&mut self.oil, Shortage::OilLow, Shortage::OilOut);
+        }
+        if moved {
+            use_one(&mut self.food, Shortage::FoodLow, Shortage::Starving);
+        }
+        shortages
+    }
+    /// describe the limits for the instructions
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![];
+        if let Some(oil) = self.oil {
+            lines.push(format!(
+                "YOUR LAMP HAS OIL FOR {} TURNS. IN THE DARK YOU CANNOT TELL WHAT IS NEARBY.",
+                oil
+            ));
+        }
+        if let Some(food) = self.food {
+            lines.push(format!(
+                "YOU HAVE FOOD FOR {} MOVES. IF IT RUNS OUT YOU STARVE.",
+                food
+            ));
+        }
+        lines
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_resources_never_run_out() {
+        let mut left = Resources::default();
+        for _ in 0..100 {
+            assert!(left.spend(true).is_empty());
+        }
+        assert!(!left.is_dark());
+    }
+    #[test]
+    fn food_runs_out_on_moves_and_starves_the_player() {
+        let mut left = Resources {
+            food: Some(LOW + 1),
+            ..Resources::default()
+        };
+        assert!(left.spend(false).is_empty());
+        assert_eq!(left.spend(true), [Shortage::FoodLow]);
+        for _ in 1..LOW {
+            assert!(left.spend(true).is_empty());
+        }
+        let shortages = left.spend(true);
+        assert_eq!(shortages, [Shortage::Starving]);
+        assert_eq!(shortages[0].cause(), Some(Cause::Starved));
+        assert_eq!(left.food, Some(0));
+    }
+    #[test]
+    fn lamp_goes_out_without_losing() {
+        let mut left = Resources {
+            oil: Some(1),
+            ..Resources::default()
+        };
+        let shortages = left.spend(false);
+        assert_eq!(shortages, [Shortage::OilOut]);
+        assert_eq!(shortages[0].cause(), None);
+        assert!(left.is_dark());
+        // once dark, it stays dark with no more warnings
+        assert!(left.spend(true).is_empty());
+        assert_eq!(left.oil, Some(0));
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,215 @@
+/*! hand-made games played instead of the player's own: the lessons of the tutorial, puzzles, and playtests of caves designed in the editor */
+
+use crate::{
+    puzzle::{Puzzle, PuzzlePack, PuzzleView},
+    tutorial::{lesson, lesson_done, LESSON_COUNT},
+    Action, Data, Engine, GameEvent, Response,
+};
+use anyhow::{anyhow, Result};
+
+/** A `Scenario` is which hand-made game is being played. */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scenario {
+    /// a lesson of the tutorial (from 0)
+    Lesson(usize),
+    /// a puzzle, by the numbers of its pack and of the puzzle in the pack (from 0)
+    Puzzle { pack: usize, number: usize },
+    /// the cave being designed in the editor
+    Playtest,
+}
+/** A `Special` game is a [Scenario] being played, and the player's own game to go back to afterwards. */
+#[derive(Debug)]
+pub struct Special {
+    scenario: Scenario,
+    saved: Data,
+}
+impl Engine {
+    /** `solves` checks a solution to a puzzle: whether the actions kill every wumpus within the moves allowed. */
+    pub fn solves(puzzle: &Puzzle, solution: &[Action]) -> Result<bool> {
+        let mut engine = Self {
+            data: puzzle.data(PuzzleView {
+                pack: 0,
+                number: 0,
+                name: puzzle.name.clone(),
+                moves: puzzle.moves,
+            })?,
+            ..Self::default()
+        };
+        for action in solution {
+            if !matches!(action, Action::Move(_) | Action::Shoot(_) | Action::Pass) {
+                return Err(anyhow!("a solution can only move, shoot and pass"));
+            }
+            engine.play(action.clone())?;
+        }
+        Ok(engine.data.f > 0)
+    }
+    /// the packs of puzzles the player can choose from
+    pub fn puzzle_packs(&self) -> &[PuzzlePack] {
+        &self.puzzle_packs
+    }
+    /// the puzzle `number` of pack `pack`, if there is one
+    fn puzzle(&self, pack: usize, number: usize) -> Option<&Puzzle> {
+        self.puzzle_packs.get(pack)?.puzzles.get(number)
+    }
+    /// start playing `scenario`, after showing `msgs`
+    pub(crate) fn start_scenario(
+        &mut self,
+        scenario: Scenario,
+        mut msgs: Vec<String>,
+    ) -> Result<Response> {
+        let mut data = match scenario {
+            Scenario::Lesson(number) => lesson(number),
+            Scenario::Puzzle { pack, number } => match self.puzzle(pack, number) {
+                Some(puzzle) => puzzle.data(PuzzleView {
+                    pack,
+                    number,
+                    name: puzzle.name.clone(),
+                    moves: puzzle.moves,
+                })?,
+                None => {
+                    self.data.say("NO SUCH PUZZLE");
+                    return Ok(self.data.create_response());
+                }
+            },
+            Scenario::Playtest => self.editor.design().data()?,
+        };
+        msgs.append(&mut data.msgs);
+        data.msgs = msgs;
+        data.events = carry_events(&mut self.data, data.events);
+        let saved = match self.special.take() {
+            Some(special) => {
+                self.data = data;
+                special.saved
+            }
+            None => std::mem::replace(&mut self.data, data),
+        };
+        // the player's own high scores and statistics are shown while the scenario is played
+        self.data.high_scores = saved.high_scores.clone();
+        self.data.stats = saved.stats.clone();
+        self.special = Some(Special { scenario, saved });
+        Ok(self.data.create_response())
+    }
+    /// carry out `action` during a [Scenario]: check whether the player has done what it asks, and move on to the next lesson of the tutorial
+    pub(crate) fn play_scenario(&mut self, action: Action) -> Result<Response> {
+        let scenario = match &self.special {
+            Some(special) => special.scenario,
+            None => return self.play(action),
+        };
+        match action {
+            Action::ReStart => self.start_scenario(scenario, vec![]),
+            Action::StartTutorial => self.start_scenario(Scenario::Lesson(0), vec![]),
+            Action::StartPuzzle(pack, number) => {
+                self.start_scenario(Scenario::Puzzle { pack, number }, vec![])
+            }
+            Action::Move(_) | Action::Shoot(_) | Action::Pass if self.data.f != 0 => {
+                self.play(action)
+            }
+            Action::Move(_) | Action::Shoot(_) | Action::Pass => {
+                let mut response = self.play(action.clone())?;
+                match scenario {
+                    Scenario::Lesson(number) if lesson_done(number, &self.data, &action) => {
+                        let msgs = vec![response.msgs.clone(), "WELL DONE!".to_string()];
+                        if number + 1 < LESSON_COUNT {
+                            return self.start_scenario(Scenario::Lesson(number + 1), msgs);
+                        }
+                        self.leave_scenario();
+                        self.data.renew();
+                        self.data.msgs = msgs;
+                        self.data
+                            .say("YOU HAVE FINISHED THE TUTORIAL. NOW HUNT THE WUMPUS FOR REAL!");
+                        return Ok(self.data.create_response());
+                    }
+                    Scenario::Lesson(_) if self.data.f != 0 => response
+                        .msgs
+                        .push_str("<br/>PRESS RESTART TO TRY THIS LESSON AGAIN"),
+                    Scenario::Puzzle { .. } if self.data.f > 0 => response.msgs.push_str(&format!(
+                        "<br/>PUZZLE SOLVED IN {} {}!",
+                        self.data.turn,
+                        if self.data.turn == 1 { "MOVE" } else { "MOVES" }
+                    )),
+                    Scenario::Puzzle { .. } if self.data.f < 0 => response
+                        .msgs
+                        .push_str("<br/>PRESS RESTART TO TRY THIS PUZZLE AGAIN"),
+                    Scenario::Playtest if self.data.f != 0 => response.msgs.push_str(
+                        "<br/>PRESS RESTART TO PLAY YOUR CAVE AGAIN, OR EDIT CAVE TO CHANGE IT",
+                    ),
+                    _ => {}
+                }
+                Ok(response)
+            }
+            Action::Hint
+            | Action::Aim(_)
+            | Action::Instructions
+            | Action::ShowKnowledge(_)
+            | Action::Replay
+            | Action::ReplayNext
+            | Action::ReplayPrevious
+            | Action::ReplayEnd
+            | Action::ShowHighScores
+            | Action::ShowStats
+            | Action::Quit => self.play(action),
+            _ => {
+                self.leave_scenario();
+                self.play(action)
+            }
+        }
+    }
+    /// go back to the game that was being played before the [Scenario]
+    pub(crate) fn leave_scenario(&mut self) {
+        if let Some(mut special) = self.special.take() {
+            special.saved.events = carry_events(&mut self.data, vec![]);
+            self.data = special.saved;
+        }
+    }
+}
+/// the events of the game being left that the observers have not been told about yet, followed by `events`
+fn carry_events(left: &mut Data, mut events: Vec<GameEvent>) -> Vec<GameEvent> {
+    let mut carried = std::mem::take(&mut left.events);
+    carried.append(&mut events);
+    carried
+}
+#[cfg(test)]
+mod tests {
+    use crate::{tests::game, Action, Engine, PuzzlePack};
+    use anyhow::Result;
+    use narthex_engine_trait::EngineTrait;
+
+    #[test]
+    fn looking_at_the_scores_keeps_the_lesson() -> Result<()> {
+        let mut engine = game("{}")?;
+        engine.data.stats.games = 3;
+        engine.execute(Action::StartTutorial)?;
+        let response = engine.execute(Action::ShowStats)?;
+        assert!(response.msgs.contains("YOU HAVE PLAYED 3 GAMES"));
+        assert!(response.lesson.is_some());
+        let response = engine.execute(Action::ShowHighScores)?;
+        assert!(response.lesson.is_some());
+        assert!(engine.special.is_some());
+
+        // starting another game does leave the tutorial
+        let response = engine.execute(Action::StartDaily)?;
+        assert!(response.lesson.is_none());
+        assert!(engine.special.is_none());
+        assert_eq!(engine.data.stats.games, 3);
+        Ok(())
+    }
+    #[test]
+    fn impossible_moves_do_not_count_against_a_puzzle() -> Result<()> {
+        let mut engine = game("{}")?;
+        engine.execute(Action::StartPuzzle(0, 0))?;
+        let response = engine.execute(Action::Move(13))?;
+        assert!(response.msgs.contains("NOT POSSIBLE"));
+        let response = engine.execute(Action::Shoot(vec![5]))?;
+        assert!(response.msgs.contains("PUZZLE SOLVED IN 1 MOVE!"));
+
+        let puzzle = &PuzzlePack::shipped()?[0].puzzles[0];
+        assert!(Engine::solves(
+            puzzle,
+            &[Action::Move(13), Action::Shoot(vec![5])]
+        )?);
+        Ok(())
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
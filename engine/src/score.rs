@@ -0,0 +1,184 @@
+/*! the score for a game, and the table of high scores */
+
+use crate::{daily::Daily, Action};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// the number of scores kept in the table
+const TABLE_SIZE: usize = 10;
+
+/** A `Play` is what the player did in a game that they won, for working out the score. */
+#[derive(Debug, Clone, Copy)]
+pub struct Play {
+    /// the number of wumpuses killed
+    pub killed: usize,
+    /// the number of turns taken
+    pub turns: u32,
+    /// the number of arrows left
+    pub arrows: i8,
+    /// the number of rooms the player went into
+    pub explored: usize,
+    /// how much harder than the classic game the settings make it, as a percentage (100 for the classic game)
+    pub difficulty: u32,
+}
+impl Play {
+    /// the score: points for each wumpus, arrow left and room explored, less points for each turn, all scaled by the difficulty
+    pub fn score(&self) -> u32 {
+        let points =
+            100 * self.killed as i64 + 10 * self.arrows.max(0) as i64 + 5 * self.explored as i64
+                - 2 * self.turns as i64;
+        (points.max(10) * self.difficulty as i64 / 100) as u32
+    }
+}
+/** A `HighScore` is an entry in the [HighScores] table. */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    /// the number of turns taken
+    pub turns: u32,
+    /// the number of wumpuses killed
+    pub killed: usize,
+    /// the number of rooms the player went into
+    pub explored: usize,
+    /// the daily challenge, if this was one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<Daily>,
+    /// what the player did in a daily challenge, so that the score can be checked by playing the game again
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<Action>,
+}
+/** `HighScores` are the best scores so far, best first. */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct HighScores {
+    scores: Vec<HighScore>,
+}
+impl HighScores {
+    /// read the table from a JSON file, or start an empty one if there is no file yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+    /// write the table to a JSON file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+    /// read the table from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+    /// the table as JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&self.scores)?)
+    }
+    /// add a score, returning its place in the table (from 0) if it is good enough to go in
+    pub fn add(&mut self, entry: HighScore) -> Option<usize> {
+        let place = self
+            .scores
+            .iter()
+            .position(|other| entry.score > other.score)
+            .unwrap_or(self.scores.len());
+        if place >= TABLE_SIZE {
+            return None;
+        }
+        self.scores.insert(place, entry);
+        self.scores.truncate(TABLE_SIZE);
+        Some(place)
+    }
+    /// the scores, best first
+    pub fn scores(&self) -> &[HighScore] {
+        &self.scores
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn entry(score: u32) -> HighScore {
+        HighScore {
+            score,
+            turns: 10,
+            killed: 1,
+            explored: 5,
+            daily: None,
+            actions: vec![],
+        }
+    }
+    fn scores(table: &HighScores) -> Vec<u32> {
+        table.scores().iter().map(|entry| entry.score).collect()
+    }
+    #[test]
+    fn score_rewards_kills_arrows_and_exploring_and_costs_turns() {
+        let play = Play {
+            killed: 1,
+            turns: 10,
+            arrows: 4,
+            explored: 6,
+            difficulty: 100,
+        };
+        assert_eq!(play.score(), 100 + 40 + 30 - 20);
+        assert_eq!(
+            Play {
+                difficulty: 150,
+                ..play
+            }
+            .score(),
+            225
+        );
+        // a long game still scores something
+        assert_eq!(
+            Play {
+                turns: 1000,
+                ..play
+            }
+            .score(),
+            10
+        );
+    }
+    #[test]
+    fn table_keeps_the_best_scores_first() {
+        let mut table = HighScores::default();
+        assert_eq!(table.add(entry(50)), Some(0));
+        assert_eq!(table.add(entry(80)), Some(0));
+        assert_eq!(table.add(entry(50)), Some(2));
+        assert_eq!(table.add(entry(60)), Some(1));
+        assert_eq!(scores(&table), [80, 60, 50, 50]);
+        for score in 0..10 {
+            table.add(entry(100 + score));
+        }
+        assert_eq!(table.scores().len(), TABLE_SIZE);
+        assert_eq!(table.scores()[0].score, 109);
+        assert_eq!(table.scores()[TABLE_SIZE - 1].score, 100);
+        assert_eq!(table.add(entry(100)), None);
+        assert_eq!(table.add(entry(105)), Some(5));
+        assert_eq!(table.scores()[TABLE_SIZE - 1].score, 101);
+    }
+    #[test]
+    fn table_is_saved_and_loaded() -> Result<()> {
+        let mut table = HighScores::default();
+        table.add(entry(70));
+        table.add(HighScore {
+            daily: Some(Daily::on_day(20745)),
+            actions: vec![Action::Move(2), Action::Shoot(vec![3, 4])],
+            ..entry(90)
+        });
+        let read = HighScores::from_json(&table.to_json()?)?;
+        assert_eq!(read.scores(), table.scores());
+
+        let path = env::temp_dir().join(format!("wumpus-scores-{}.json", std::process::id()));
+        assert!(HighScores::load(&path)?.scores().is_empty());
+        table.save(&path)?;
+        let loaded = HighScores::load(&path);
+        fs::remove_file(&path)?;
+        assert_eq!(loaded?.scores(), table.scores());
+        Ok(())
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,135 @@
+/*! the view of someone watching a game rather than playing it, who can see everything */
+
+use crate::{map::Map, replay::HazardRooms, Data, Pile, PlayerPanel, Resources, Status};
+use getset::{CopyGetters, Getters};
+use serde::Serialize;
+
+/** A `SpectatorView` is the whole state of the game after a turn, including where the wumpuses and hazards are, for teaching or streaming. Players never see this: they get a [crate::Response]. */
+#[derive(Debug, Clone, Serialize, Getters, CopyGetters)]
+pub struct SpectatorView {
+    /// the number of turns (moves and shots) taken
+    #[getset(get_copy = "pub")]
+    turn: u32,
+    /// whether the game is over
+    #[getset(get_copy = "pub")]
+    status: Status,
+    /// the number of the player whose turn it is, from 1
+    #[getset(get_copy = "pub")]
+    player: usize,
+    /// where each player is, and what everyone can see about them
+    #[getset(get = "pub")]
+    players: Vec<SpectatedPlayer>,
+    /// the rooms the wumpuses still alive are in
+    #[getset(get = "pub")]
+    wumpuses: Vec<u8>,
+    /// the rooms with each hazard
+    #[getset(get = "pub")]
+    hazards: Vec<HazardRooms>,
+    /// all the arrows lying in the cave, whether or not anyone knows they are there
+    #[getset(get = "pub")]
+    piles: Vec<Pile>,
+    /// the lamp oil and food the player whose turn it is has left
+    #[getset(get_copy = "pub")]
+    resources: Resources,
+    /// all the messages about the latest turn, including those only the player could see
+    #[getset(get = "pub")]
+    msgs: String,
+    /// a map of the cave showing where everything is, as SVG
+    #[getset(get = "pub")]
+    map: String,
+}
+/** A `SpectatedPlayer` is a player as a spectator sees them. */
+#[derive(Debug, Clone, Serialize)]
+pub struct SpectatedPlayer {
+    #[serde(flatten)]
+    pub panel: PlayerPanel,
+    /// the room the player is in
+    pub room: u8,
+}
+impl SpectatorView {
+    /// see everything about the game
+    pub(crate) fn new(data: &Data) -> Self {
+        let mut players: Vec<SpectatedPlayer> = data
+            .waiting
+            .iter()
+            .map(|player| SpectatedPlayer {
+                panel: player.panel(),
+                room: player.room,
+            })
+            .collect();
+        players.push(SpectatedPlayer {
+            panel: PlayerPanel {
+                number: data.player,
+                arrows: data.a,
+                killed: data.player_kills,
+                out: data.cause,
+                current: true,
+            },
+            room: data.l[0],
+        });
+        players.sort_by_key(|player| player.panel.number);
+        let hazards = HazardRooms::all(&data.hazards);
+        let mut map = Map::new(&data.cave, Some(data.l[0]));
+        for player in players.iter().filter(|player| !player.panel.current) {
+            map.label(player.room, &format!("PLAYER {}", player.panel.number));
+        }
+        for wumpus in &data.l[1..] {
+            map.label(*wumpus, "WUMPUS");
+            map.mark(*wumpus, "wumpus");
+        }
+        for hazard in &hazards {
+            hazard.draw(&mut map);
+        }
+        for pile in &data.piles {
+            map.label(pile.room, &format!("{} ARROWS", pile.arrows));
+        }
+        Self {
+            turn: data.turn,
+            status: data.status(),
+            player: data.player,
+            players,
+            wumpuses: data.l[1..].to_vec(),
+            hazards,
+            piles: data.piles.clone(),
+            resources: data.left,
+            msgs: data.msgs.join("<br/>"),
+            map: map.render(),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use crate::{tests::game, Action};
+    use anyhow::Result;
+    use narthex_engine_trait::EngineTrait;
+
+    #[test]
+    fn spectators_see_what_players_cannot() -> Result<()> {
+        let mut engine = game(r#"{"quivers": 1, "seed": 3}"#)?;
+        let response = engine.execute(Action::Instructions)?;
+        let view = engine.spectate();
+
+        // the spectator sees where everything is
+        assert_eq!(view.wumpuses(), &engine.data.l[1..]);
+        let hazards = serde_json::to_value(view.hazards())?;
+        for (i, placed) in engine.data.hazards.iter().enumerate() {
+            assert_eq!(hazards[i]["rooms"], serde_json::json!(placed.rooms));
+        }
+        assert_eq!(view.piles().len(), 1);
+        assert!(view.map().contains(">WUMPUS<"));
+
+        // the player sees none of it
+        assert!(response.piles().is_empty());
+        assert!(!response.map.contains(">WUMPUS<"));
+        assert!(!response.map.contains(">PIT<"));
+        let json = serde_json::to_value(&response)?;
+        assert!(json.get("hazards").is_none());
+        assert!(json
+            .get("wumpuses")
+            .is_some_and(|wumpuses| wumpuses.is_u64()));
+        Ok(())
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
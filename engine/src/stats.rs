@@ -0,0 +1,125 @@
+/*! statistics kept over all the games a player has played, and the achievements they have unlocked */
+
+use crate::{Cause, Status};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+/** A `GameTally` counts what happened during one game. */
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GameTally {
+    /// the number of arrows shot
+    pub shots: u32,
+    /// the number of times the player was carried off by bats
+    pub bat_rides: u32,
+    /// the number of wumpuses killed
+    pub kills: u32,
+    /// the most rooms an arrow went through to kill a wumpus
+    pub longest_kill: usize,
+}
+/** An `Achievement` is something the player can do once to unlock it. */
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Display, EnumIter,
+)]
+pub enum Achievement {
+    FirstBlood,
+    LongShot,
+    Marksman,
+    FrequentFlyer,
+    Survivor,
+    Veteran,
+    Champion,
+}
+impl Achievement {
+    /// what the player did to unlock it
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::FirstBlood => "KILLED A WUMPUS",
+            Self::LongShot => "KILLED A WUMPUS WITH A 5-ROOM ARROW",
+            Self::Marksman => "WON WITHOUT MISSING",
+            Self::FrequentFlyer => "CARRIED OFF BY BATS 3 TIMES IN ONE GAME",
+            Self::Survivor => "LASTED 50 TURNS IN ONE GAME",
+            Self::Veteran => "PLAYED 50 GAMES",
+            Self::Champion => "WON 10 GAMES",
+        }
+    }
+    /// whether it has been earned, by the game just finished or over all the games so far
+    fn earned(self, stats: &Stats, status: Status, turns: u32, game: &GameTally) -> bool {
+        match self {
+            Self::FirstBlood => game.kills > 0,
+            Self::LongShot => game.longest_kill >= 5,
+            Self::Marksman => status == Status::Won && game.shots == game.kills,
+            Self::FrequentFlyer => game.bat_rides >= 3,
+            Self::Survivor => turns >= 50,
+            Self::Veteran => stats.games >= 50,
+            Self::Champion => stats.wins >= 10,
+        }
+    }
+}
+/** `Stats` are kept over all the games a player has played. */
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    /// the number of games finished
+    pub games: u32,
+    pub wins: u32,
+    /// the number of games lost, by how they were lost
+    pub losses: BTreeMap<Cause, u32>,
+    /// the most turns taken in one game
+    pub longest_survival: u32,
+    /// the number of times the player has been carried off by bats
+    pub bat_rides: u32,
+    /// the number of arrows shot
+    pub shots: u32,
+    /// the number of wumpuses killed
+    pub kills: u32,
+    /// the achievements unlocked so far, in the order they were unlocked
+    pub achievements: Vec<Achievement>,
+}
+impl Stats {
+    /// read the statistics from a JSON file, or start afresh if there is no file yet
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+    /// write the statistics to a JSON file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+    /// read the statistics from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+    /// the statistics as JSON
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+    /// add a finished game, returning any achievements it unlocked
+    pub fn add(&mut self, status: Status, turns: u32, game: &GameTally) -> Vec<Achievement> {
+        self.games += 1;
+        match status {
+            Status::Won => self.wins += 1,
+            Status::Lost(cause) => *self.losses.entry(cause).or_default() += 1,
+            Status::Playing => {}
+        }
+        self.longest_survival = self.longest_survival.max(turns);
+        self.bat_rides += game.bat_rides;
+        self.shots += game.shots;
+        self.kills += game.kills;
+        let unlocked: Vec<Achievement> = Achievement::iter()
+            .filter(|achievement| {
+                !self.achievements.contains(achievement)
+                    && achievement.earned(self, status, turns, game)
+            })
+            .collect();
+        self.achievements.extend(&unlocked);
+        unlocked
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,216 @@
+/*! the tutorial: lessons in hand-made caves that teach the senses and the arrows one at a time */
+
+use crate::{Action, Cave, Config, Data};
+use serde::Serialize;
+
+/** An `Objective` is what the player has to do to finish a [Lesson]. */
+#[derive(Debug, Clone, Copy)]
+enum Objective {
+    /// kill the wumpus
+    Kill,
+    /// get to a room
+    Reach(u8),
+    /// be carried off by bats (and survive)
+    RideBats,
+    /// kill the wumpus with an arrow that goes through more than one room
+    KillCrooked,
+}
+impl Objective {
+    /// whether the player has done it, after `action`
+    fn met(self, data: &Data, action: &Action) -> bool {
+        match self {
+            Self::Kill => data.f > 0,
+            Self::Reach(room) => data.f == 0 && data.l[0] == room,
+            Self::RideBats => data.f == 0 && data.tally.bat_rides > 0,
+            Self::KillCrooked => {
+                data.f > 0 && matches!(action, Action::Shoot(path) if path.len() > 1)
+            }
+        }
+    }
+}
+/** A `Lesson` is one scenario of the tutorial: where everything is, how the dice fall, and what the player has to do. */
+#[derive(Debug)]
+struct Lesson {
+    title: &'static str,
+    /// what the player is told at the start
+    intro: &'static [&'static str],
+    /// what the player has to do, in a few words
+    task: &'static str,
+    objective: Objective,
+    /// the room the player starts in
+    room: u8,
+    wumpus: u8,
+    pits: &'static [u8],
+    bats: &'static [u8],
+    /// the seed for the dice, so that the wumpus and the bats do the same every time
+    seed: u64,
+}
+/// the lessons, in order, all in the classic cave
+const LESSONS: [Lesson; 4] = [
+    Lesson {
+        title: "SMELL",
+        intro: &[
+            "THE WUMPUS SMELLS. WHEN IT IS IN A ROOM NEXT TO YOURS YOU CAN SMELL IT, BUT NOT TELL WHICH ROOM IT IS IN.",
+            "MOVE AROUND TO FIND OUT WHERE IT IS, THEN SHOOT AN ARROW INTO ITS ROOM. IF YOU MISS, THE NOISE MAY WAKE IT UP.",
+        ],
+        task: "KILL THE WUMPUS",
+        objective: Objective::Kill,
+        room: 1,
+        wumpus: 5,
+        pits: &[],
+        bats: &[],
+        seed: 1,
+    },
+    Lesson {
+        title: "DRAFT",
+        intro: &[
+            "SOME ROOMS HAVE BOTTOMLESS PITS. WHEN A PIT IS NEXT TO YOUR ROOM YOU FEEL A DRAFT.",
+            "A ROOM NEXT TO ONE WITHOUT A DRAFT CANNOT HAVE A PIT. FIND A SAFE WAY TO ROOM 10.",
+        ],
+        task: "GET TO ROOM 10",
+        objective: Objective::Reach(10),
+        room: 1,
+        wumpus: 16,
+        pits: &[2],
+        bats: &[],
+        seed: 2,
+    },
+    Lesson {
+        title: "BATS",
+        intro: &[
+            "SUPER BATS LIVE IN SOME ROOMS. WHEN THEY ARE NEXT TO YOUR ROOM YOU HEAR THEM.",
+            "IF YOU GO INTO THEIR ROOM THEY CARRY YOU OFF TO ANOTHER ROOM AT RANDOM, WHICH COULD BE ANYWHERE. TRY IT.",
+        ],
+        task: "GET CARRIED OFF BY THE BATS",
+        objective: Objective::RideBats,
+        room: 1,
+        wumpus: 20,
+        pits: &[],
+        bats: &[8],
+        seed: 3,
+    },
+    Lesson {
+        title: "CROOKED ARROWS",
+        intro: &[
+            "YOUR ARROWS ARE CROOKED: EACH ONE CAN FLY THROUGH UP TO 5 ROOMS, IF YOU SAY WHICH ROOMS.",
+            "THE WUMPUS IS TWO ROOMS AWAY, IN ROOM 10, SO YOU CANNOT SMELL IT. SHOOT AN ARROW THROUGH ROOM 2 INTO ROOM 10.",
+        ],
+        task: "KILL THE WUMPUS FROM TWO ROOMS AWAY",
+        objective: Objective::KillCrooked,
+        room: 1,
+        wumpus: 10,
+        pits: &[],
+        bats: &[],
+        seed: 4,
+    },
+];
+/** A `LessonView` is sent to the webview during the tutorial, to show which lesson is being played. */
+#[derive(Debug, Clone, Serialize)]
+pub struct LessonView {
+    /// the number of the lesson, from 1
+    number: usize,
+    /// the number of lessons
+    lessons: usize,
+    title: &'static str,
+    /// what the player has to do
+    task: &'static str,
+}
+/// the number of lessons in the tutorial
+pub const LESSON_COUNT: usize = LESSONS.len();
+
+/// set up the game for lesson `number` (from 0), with its introduction to show
+pub(crate) fn lesson(number: usize) -> Data {
+    let lesson = &LESSONS[number];
+    let config = Config {
+        pits: lesson.pits.len(),
+        bats: lesson.bats.len(),
+        wumpuses: 1,
+        seed: Some(lesson.seed),
+        ..Config::default()
+    };
+    let mut data = Data::new(Cave::dodecahedron(), &config);
+    data.place(
+        lesson.room,
+        &[lesson.wumpus],
+        &[lesson.pits, lesson.bats],
+        &[],
+    );
+    data.lesson = Some(LessonView {
+        number: number + 1,
+        lessons: LESSON_COUNT,
+        title: lesson.title,
+        task: lesson.task,
+    });
+    data.msgs = vec![format!(
+        "LESSON {} OF {}: {}",
+        number + 1,
+        LESSON_COUNT,
+        lesson.title
+    )];
+    for line in lesson.intro {
+        data.say(line);
+    }
+    data
+}
+/// whether the player has done what lesson `number` asks, after `action`
+pub(crate) fn lesson_done(number: usize, data: &Data, action: &Action) -> bool {
+    LESSONS[number].objective.met(data, action)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::game;
+    use anyhow::Result;
+    use narthex_engine_trait::EngineTrait;
+
+    #[test]
+    fn every_lesson_can_be_finished() -> Result<()> {
+        let solutions = [
+            vec![Action::Shoot(vec![5])],
+            vec![Action::Move(8), Action::Move(9), Action::Move(10)],
+            vec![Action::Move(8)],
+            vec![Action::Shoot(vec![2, 10])],
+        ];
+        let mut engine = game("{}")?;
+        let response = engine.execute(Action::StartTutorial)?;
+        assert!(response.msgs.contains("LESSON 1 OF 4: SMELL"));
+        for (number, solution) in solutions.iter().enumerate() {
+            assert_eq!(
+                engine.data.lesson.as_ref().map(|lesson| lesson.number),
+                Some(number + 1)
+            );
+            let mut msgs = String::new();
+            for action in solution {
+                msgs = engine.execute(action.clone())?.msgs;
+            }
+            assert!(
+                msgs.contains("WELL DONE!"),
+                "lesson {}: {}",
+                number + 1,
+                msgs
+            );
+            if number + 1 < LESSON_COUNT {
+                assert!(msgs.contains(&format!(
+                    "LESSON {} OF {}: {}",
+                    number + 2,
+                    LESSON_COUNT,
+                    LESSONS[number + 1].title
+                )));
+            } else {
+                assert!(msgs.contains("YOU HAVE FINISHED THE TUTORIAL"));
+            }
+        }
+        assert!(engine.data.lesson.is_none());
+        Ok(())
+    }
+    #[test]
+    fn crooked_lesson_needs_a_crooked_arrow() {
+        let mut data = lesson(3);
+        data.f = 1;
+        assert!(!lesson_done(3, &data, &Action::Shoot(vec![10])));
+        assert!(lesson_done(3, &data, &Action::Shoot(vec![2, 10])));
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,275 @@
+/*! how the wumpus moves: the classic sleepy wumpus and some livelier ones */
+
+use crate::{dice::Dice, Cave};
+use serde::Deserialize;
+use std::fmt;
+
+/** A `Noise` is something the wumpus might notice. */
+#[derive(Debug, Clone)]
+pub enum Noise {
+    /// the player walked into the wumpus's room
+    Bump,
+    /// an arrow was shot and missed, passing through these rooms
+    Arrow(Vec<u8>),
+    /// the player finished a turn without either of the above
+    Footsteps,
+}
+/** `Surroundings` are what a wumpus can know when deciding where to go. */
+#[derive(Debug)]
+pub struct Surroundings<'a> {
+    pub cave: &'a Cave,
+    /// the room the wumpus is in
+    pub wumpus: u8,
+    /// the room the player is in
+    pub player: u8,
+}
+/** A `WumpusBehaviour` decides when the wumpus wakes and where it goes. */
+pub trait WumpusBehaviour: fmt::Debug + Send {
+    /// the wumpus hears a noise. Returns the room it is in afterwards if it was awake (even if it stayed where it was), or `None` if it slept on.
+    fn hear(&mut self, at: &Surroundings, noise: &Noise, dice: &mut Dice) -> Option<u8>;
+    /// something to add to the instructions, if the wumpus does not behave as the instructions say
+    fn description(&self) -> &'static str {
+        ""
+    }
+}
+impl Default for Box<dyn WumpusBehaviour> {
+    fn default() -> Self {
+        Box::new(Classic)
+    }
+}
+/** A `WumpusKind` selects a [WumpusBehaviour] in the [crate::Config]. */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Default)]
+pub enum WumpusKind {
+    /// sleeps until bumped into or shot at, then moves (p=.75) one room or stays still
+    #[default]
+    Classic,
+    /// always awake, so may move after every turn
+    Wanderer,
+    /// sleeps like the classic wumpus, but once woken heads for the player
+    Hunter,
+    /// runs away from arrows
+    Coward,
+    /// sleeps until there is a noise within `radius` rooms, then wanders
+    LightSleeper { radius: usize },
+}
+impl WumpusKind {
+    /// create a wumpus of this kind, ready for a new game
+    pub fn behaviour(&self) -> Box<dyn WumpusBehaviour> {
+        match self {
+            Self::Classic => Box::new(Classic),
+            Self::Wanderer => Box::new(Wanderer),
+            Self::Hunter => Box::new(Hunter::default()),
+            Self::Coward => Box::new(Coward),
+            Self::LightSleeper { radius } => Box::new(LightSleeper {
+                radius: *radius,
+                awake: false,
+            }),
+        }
+    }
+}
+/* 3370 */
+// MOVE WUMPUS ROUTINE
+/// move (p=.75) to one of the rooms next to the wumpus, or stay still
+fn stir(at: &Surroundings, dice: &mut Dice) -> u8 {
+    /* 3380 */
+    let k = dice.upto(4);
+    /* 3390 */
+    let tunnels = at.cave.tunnels(at.wumpus);
+    if k == 4 || tunnels.is_empty() {
+        at.wumpus
+    } else if tunnels.len() == 3 {
+        /* 3400 */
+        // the same roll chooses the tunnel, as in the classic cave, so that games play out as they always have
+        tunnels[k - 1]
+    } else {
+        tunnels[dice.upto(tunnels.len()) - 1]
+    }
+}
+/** The `Classic` wumpus is the one in the original game. */
+#[derive(Debug)]
+struct Classic;
+impl WumpusBehaviour for Classic {
+    fn hear(&mut self, at: &Surroundings, noise: &Noise, dice: &mut Dice) -> Option<u8> {
+        match noise {
+            Noise::Footsteps => None,
+            _ => Some(stir(at, dice)),
+        }
+    }
+}
+/** A `Wanderer` never sleeps. */
+#[derive(Debug)]
+struct Wanderer;
+impl WumpusBehaviour for Wanderer {
+    fn hear(&mut self, at: &Surroundings, _noise: &Noise, dice: &mut Dice) -> Option<u8> {
+        Some(stir(at, dice))
+    }
+    fn description(&self) -> &'static str {
+        "THIS WUMPUS NEVER SLEEPS. IT MAY MOVE AFTER ANY TURN."
+    }
+}
+/** A `Hunter` sleeps until woken, and then comes for the player. */
+#[derive(Debug, Default)]
+struct Hunter {
+    awake: bool,
+}
+impl WumpusBehaviour for Hunter {
+    fn hear(&mut self, at: &Surroundings, noise: &Noise, dice: &mut Dice) -> Option<u8> {
+        match noise {
+            Noise::Footsteps if !self.awake => return None,
+            _ => self.awake = true,
+        }
+        if dice.upto(4) == 4 {
+            return Some(at.wumpus);
+        }
+        let nearest = at
+            .cave
+            .tunnels(at.wumpus)
+            .iter()
+            .filter_map(|room| {
+                at.cave.distances(*room)[at.player as usize - 1].map(|distance| (distance, *room))
+            })
+            .min();
+        Some(nearest.map_or(at.wumpus, |(_, room)| room))
+    }
+    fn description(&self) -> &'static str {
+        "THIS WUMPUS HUNTS. ONCE AWAKE, IT COMES FOR YOU."
+    }
+}
+/** A `Coward` runs from arrows to a room the arrow did not go through, as far from the player as it can. */
+#[derive(Debug)]
+struct Coward;
+impl WumpusBehaviour for Coward {
+    fn hear(&mut self, at: &Surroundings, noise: &Noise, dice: &mut Dice) -> Option<u8> {
+        match noise {
+            Noise::Footsteps => None,
+            Noise::Bump => Some(stir(at, dice)),
+            Noise::Arrow(path) => {
+                let from_player = at.cave.distances(at.player);
+                let farthest = at
+                    .cave
+                    .tunnels(at.wumpus)
+                    .iter()
+                    .filter(|room| !path.contains(room))
+                    .map(|room| (from_player[*room as usize - 1].unwrap_or(usize::MAX), *room))
+                    .max();
+                Some(farthest.map_or(at.wumpus, |(_, room)| room))
+            }
+        }
+    }
+    fn description(&self) -> &'static str {
+        "THIS WUMPUS IS A COWARD. IT RUNS AWAY FROM ARROWS."
+    }
+}
+/** A `LightSleeper` wakes when there is any noise close enough, and then never sleeps again. */
+#[derive(Debug)]
+struct LightSleeper {
+    /// how many rooms away a noise wakes the wumpus
+    radius: usize,
+    awake: bool,
+}
+impl WumpusBehaviour for LightSleeper {
+    fn hear(&mut self, at: &Surroundings, noise: &Noise, dice: &mut Dice) -> Option<u8> {
+        if !self.awake {
+            let distances = at.cave.distances(at.wumpus);
+            let near = |room: &u8| {
+                distances[*room as usize - 1].is_some_and(|distance| distance <= self.radius)
+            };
+            self.awake = match noise {
+                Noise::Bump => true,
+                Noise::Arrow(path) => path.iter().any(near),
+                Noise::Footsteps => near(&at.player),
+            };
+        }
+        if self.awake {
+            Some(stir(at, dice))
+        } else {
+            None
+        }
+    }
+    fn description(&self) -> &'static str {
+        "THIS WUMPUS SLEEPS LIGHTLY. ANY NOISE NEARBY WAKES IT, AND THEN IT WANDERS."
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// where a wumpus in room 1 of `cave` goes when stirred, over many tries
+    fn destinations(cave: &Cave) -> Vec<u8> {
+        let mut dice = Dice::new(Some(1));
+        let at = Surroundings {
+            cave,
+            wumpus: 1,
+            player: 2,
+        };
+        let mut rooms: Vec<u8> = (0..400).map(|_| stir(&at, &mut dice)).collect();
+        rooms.sort_unstable();
+        rooms.dedup();
+        rooms
+    }
+    #[test]
+    fn stirred_wumpus_uses_every_tunnel() {
+        let star = Cave::new(vec![
+            vec![2, 3, 4, 5, 6],
+            vec![1],
+            vec![1],
+            vec![1],
+            vec![1],
+            vec![1],
+        ]);
+        assert_eq!(destinations(&star), [1, 2, 3, 4, 5, 6]);
+        let pair = Cave::new(vec![vec![2], vec![1]]);
+        assert_eq!(destinations(&pair), [1, 2]);
+        assert_eq!(destinations(&Cave::dodecahedron()), [1, 2, 5, 8]);
+    }
+    #[test]
+    fn every_kind_of_wumpus_stays_or_moves_one_room() {
+        let cave = Cave::dodecahedron();
+        let kinds = [
+            WumpusKind::Classic,
+            WumpusKind::Wanderer,
+            WumpusKind::Hunter,
+            WumpusKind::Coward,
+            WumpusKind::LightSleeper { radius: 2 },
+        ];
+        let noises = [Noise::Bump, Noise::Arrow(vec![2, 3]), Noise::Footsteps];
+        let mut dice = Dice::new(Some(2));
+        for kind in &kinds {
+            let mut behaviour = kind.behaviour();
+            let mut wumpus = 1;
+            for turn in 0..300 {
+                let at = Surroundings {
+                    cave: &cave,
+                    wumpus,
+                    player: (turn % 20) as u8 + 1,
+                };
+                if let Some(room) = behaviour.hear(&at, &noises[turn % noises.len()], &mut dice) {
+                    assert!(
+                        room == wumpus || cave.is_tunnel(wumpus, room),
+                        "{:?} wumpus went from {} to {}",
+                        kind,
+                        wumpus,
+                        room
+                    );
+                    wumpus = room;
+                }
+            }
+        }
+    }
+    #[test]
+    fn classic_wumpus_sleeps_through_footsteps() {
+        let cave = Cave::dodecahedron();
+        let at = Surroundings {
+            cave: &cave,
+            wumpus: 1,
+            player: 2,
+        };
+        let mut dice = Dice::new(Some(3));
+        let mut classic = WumpusKind::Classic.behaviour();
+        assert_eq!(classic.hear(&at, &Noise::Footsteps, &mut dice), None);
+        assert!(classic.hear(&at, &Noise::Bump, &mut dice).is_some());
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -30,48 +30,311 @@ var invoke = function (arg) {
     respond( response_json);
 };
 {% endmatch %}
-var oldEventListeners = Array(3);
 // process response
 var respond = function (response_str) {
     console.log("handling response " + response_str);
     var response = JSON.parse(response_str);
     setTag("msgs", response.msgs);
-    for (i = 0; i < 3; i++) {
+    document.getElementById("game").hidden = !!response.replay || !!response.editor;
+    document.getElementById("replay").hidden = !response.replay;
+    document.getElementById("editor").hidden = !response.editor;
+    if (response.replay) {
+        showReplay(response);
+        return;
+    }
+    if (response.editor) {
+        showEditor(response.editor);
+        return;
+    }
+    pauseReplay();
+    // the game can be replayed once it is over, as the replay shows where everything is
+    document.getElementById("replaystart").hidden = response.status == "Playing";
+    showResources(response.resources, response.goal, response.turn);
+    showLesson(response.lesson);
+    showPuzzle(response.puzzle);
+    showHighScores(response.high_scores);
+    showPlayers(response.players);
+    document.getElementById("knowledge").checked = response.knowledge.length > 0;
+    var moves = document.getElementById("moves");
+    moves.innerHTML = "";
+    tunnels = response.tunnels;
+    for (i = 0; i < response.tunnels.length; i++) {
         let tunnel = response.tunnels[i];
-        var tag = "move" + i;
-        var s = document.getElementById(tag);
-        if (!s) console.error("no tag called '" + tag + "'");
-        var svalue = "room " + tunnel;
-        s.textContent = svalue;
-        var newEventListener = function () {
-            var t = tunnel;
-            console.log("go to " + t);
-            invoke({ Move: t })
-        };
+        var s = document.createElement("button");
+        s.textContent = "room " + tunnel;
+        if (i < 9) s.setAttribute("aria-keyshortcuts", String(i + 1));
+        s.addEventListener('click', function () {
+            console.log("go to " + tunnel);
+            invoke({ Move: tunnel })
+        }, false);
         console.log('set up move for ' + tunnel);
-        if (oldEventListeners[i])
-            s.removeEventListener('click', oldEventListeners[i], false);
-        oldEventListeners[i] = newEventListener;
-        s.addEventListener('click', newEventListener, false);
+        moves.appendChild(s);
+        moves.appendChild(document.createTextNode(" "));
     }
+    plainMap = response.map;
+    showAim(response.aim);
+    animatePath(response.arrow_path, "arrow", function () {
+        animatePath(response.bat_path, "bats");
+    });
     console.log("processed response");
 }
+// the arrow being aimed, if one is, with the map to show when it is not
+var aiming = null;
+var plainMap = "";
+var showAim = function (aim) {
+    aiming = aim;
+    document.getElementById("aimstart").disabled = !!aim;
+    document.getElementById("aimback").disabled = !aim || aim.path.length == 0;
+    document.getElementById("shoot").disabled = !aim || aim.path.length == 0;
+    document.getElementById("aimstop").disabled = !aim;
+    document.querySelectorAll("#moves button").forEach(function (button) {
+        button.disabled = !!aim;
+    });
+    document.getElementById("targetlabel").hidden = !aim || aim.next.length == 0;
+    var targets = document.getElementById("targets");
+    targets.innerHTML = "";
+    if (!aim) {
+        setTag("map", plainMap);
+        setTag("aimpath", "Aim an arrow, then click on the rooms on the map, or the buttons, to shoot it through.");
+        return;
+    }
+    setTag("map", aim.map);
+    var through = aim.path.length ? "Arrow aimed through room " + aim.path.join(", ") : "Choose the first room to shoot the arrow through";
+    setTag("aimpath", through + (aim.next.length ? "" : " (it can fly no further)"));
+    aim.next.forEach(function (room, i) {
+        var button = document.createElement("button");
+        button.textContent = "room " + room;
+        if (i < 9) button.setAttribute("aria-keyshortcuts", String(i + 1));
+        button.addEventListener("click", function () {
+            aimAt(room);
+        }, false);
+        targets.appendChild(button);
+        targets.appendChild(document.createTextNode(" "));
+    });
+};
+var startAiming = function () {
+    invoke({ Aim: [] });
+};
+// aim the arrow through one more room, clicked on the map or chosen from the buttons
+var aimAt = function (room) {
+    invoke({ Aim: aiming.path.concat([room]) });
+};
+var aimBack = function () {
+    invoke({ Aim: aiming.path.slice(0, -1) });
+};
+var stopAiming = function () {
+    showAim(null);
+};
 var shoot = function () {
+    if (!aiming || aiming.path.length == 0) return;
     console.log("shooting arrow");
-    var rooms = [];
-    var ids = ['arrow0', 'arrow1', 'arrow2', 'arrow3', 'arrow4'];
-    for (id in ids) {
-        var ii = document.getElementById(ids[id]);
-        if (!ii) console.error("no tag called '" + id + "'");
-        var v = parseInt(ii.value);
-       // console.log("value " + v + " type " + typeof v);
-        if (!isNaN(v)) {
-            rooms.push(v);
+    invoke({ Shoot: aiming.path });
+};
+// show how an arrow flew or where bats carried the player, room by room, leaving a trail on the map
+var pathTimer = null;
+var animatePath = function (path, kind, then) {
+    if (pathTimer) clearInterval(pathTimer);
+    pathTimer = null;
+    var svg = document.querySelector("#map svg");
+    if (!svg || path.length < 2) {
+        if (then) then();
+        return;
+    }
+    var points = path.map(function (room) {
+        var circle = svg.querySelector('g[data-room="' + room + '"] circle');
+        return [Number(circle.getAttribute("cx")), Number(circle.getAttribute("cy"))];
+    });
+    var ns = "http://www.w3.org/2000/svg";
+    var trail = document.createElementNS(ns, "polyline");
+    trail.setAttribute("class", "trail " + kind);
+    svg.appendChild(trail);
+    var mover = document.createElementNS(ns, "circle");
+    mover.setAttribute("class", "mover " + kind);
+    mover.setAttribute("r", 1.5);
+    svg.appendChild(mover);
+    var stepsPerRoom = 12;
+    var step = 0;
+    pathTimer = setInterval(function () {
+        var leg = Math.floor(step / stepsPerRoom);
+        if (leg >= points.length - 1) {
+            clearInterval(pathTimer);
+            pathTimer = null;
+            svg.removeChild(mover);
+            if (then) then();
+            return;
         }
-        ii.value = "";
+        var t = (step % stepsPerRoom) / stepsPerRoom;
+        var from = points[leg];
+        var to = points[leg + 1];
+        var at = [from[0] + (to[0] - from[0]) * t, from[1] + (to[1] - from[1]) * t];
+        mover.setAttribute("cx", at[0]);
+        mover.setAttribute("cy", at[1]);
+        trail.setAttribute("points", points.slice(0, leg + 1).concat([at]).join(" "));
+        step++;
+        if (step == stepsPerRoom * (points.length - 1)) trail.setAttribute("points", points.join(" "));
+    }, 40);
+};
+// the time, lamp oil and food left, if they are limited
+var showResources = function (resources, goal, turn) {
+    var left = [];
+    if (goal.KillAllWithin) left.push("turns left: " + Math.max(goal.KillAllWithin.turns - turn, 0));
+    if (resources.oil !== null) left.push("lamp oil: " + resources.oil);
+    if (resources.food !== null) left.push("food: " + resources.food);
+    setTag("resources", left.join(", "));
+};
+// the lesson being played, during the tutorial
+var showLesson = function (lesson) {
+    var tag = document.getElementById("lesson");
+    tag.hidden = !lesson;
+    if (!lesson) return;
+    tag.textContent = "Lesson " + lesson.number + " of " + lesson.lessons + " (" + lesson.title + "): " + lesson.task;
+};
+// the puzzle being played, if any
+var showPuzzle = function (puzzle) {
+    var tag = document.getElementById("puzzlename");
+    tag.hidden = !puzzle;
+    if (!puzzle) return;
+    tag.textContent = "Puzzle: " + puzzle.name + " (solve in " + puzzle.moves + (puzzle.moves == 1 ? " move)" : " moves)");
+};
+// start the puzzle chosen
+var startPuzzle = function () {
+    var chosen = document.getElementById("puzzle").value.split(",").map(Number);
+    invoke({ StartPuzzle: chosen });
+};
+// a panel for each player in a hot-seat game
+var showPlayers = function (players) {
+    var panels = document.getElementById("players");
+    panels.innerHTML = "";
+    for (var i = 0; i < players.length; i++) {
+        var player = players[i];
+        var panel = document.createElement("div");
+        panel.className = player.current ? "player current" : "player";
+        var state = player.out ? "out (" + player.out + ")" : player.current ? "to play" : "waiting";
+        panel.textContent = "Player " + player.number + ": " + state + ", arrows " + player.arrows + ", wumpuses killed " + player.killed;
+        panels.appendChild(panel);
     }
-    invoke({ Shoot: rooms });
+};
+// the table of high scores, if asked for
+var showHighScores = function (scores) {
+    var table = document.getElementById("highscores");
+    table.hidden = !scores;
+    if (!scores) return;
+    var rows = document.getElementById("highscorerows");
+    rows.innerHTML = "";
+    for (var i = 0; i < scores.length; i++) {
+        var row = document.createElement("tr");
+        [scores[i].score, scores[i].turns, scores[i].killed, scores[i].explored, scores[i].daily ? scores[i].daily.date : ""].forEach(function (value) {
+            var cell = document.createElement("td");
+            cell.textContent = value;
+            row.appendChild(cell);
+        });
+        rows.appendChild(row);
+    }
+};
+// keyboard controls: a number to move through that tunnel (or, while aiming, to aim the arrow through that room), S to aim an arrow, Backspace to take back a room, Enter to shoot it and Escape to stop aiming
+var tunnels = [];
+document.addEventListener("keydown", function (event) {
+    var target = event.target;
+    if (event.ctrlKey || event.altKey || event.metaKey) return;
+    if (target.getAttribute && target.getAttribute("role") == "button" && (event.key == "Enter" || event.key == " ")) {
+        event.preventDefault();
+        target.dispatchEvent(new Event("click"));
+        return;
+    }
+    var typing = (target.tagName == "INPUT" && target.type != "button") || /^(SELECT|TEXTAREA)$/.test(target.tagName);
+    if (typing || document.getElementById("game").hidden) return;
+    // Enter on a button presses it
+    var onButton = /^(INPUT|BUTTON)$/.test(target.tagName);
+    var number = parseInt(event.key);
+    var rooms = aiming ? aiming.next : tunnels;
+    if (number >= 1 && number <= rooms.length) {
+        event.preventDefault();
+        if (aiming) aimAt(rooms[number - 1]);
+        else invoke({ Move: rooms[number - 1] });
+    } else if (aiming && event.key == "Backspace" && aiming.path.length > 0) {
+        event.preventDefault();
+        aimBack();
+    } else if (aiming && event.key == "Enter" && !onButton) {
+        event.preventDefault();
+        shoot();
+    } else if (aiming && event.key == "Escape") {
+        stopAiming();
+    } else if (!aiming && (event.key == "s" || event.key == "S")) {
+        event.preventDefault();
+        startAiming();
+    }
+});
+// the cave editor
+var design = null;
+var tunnelFrom = null;
+var showEditor = function (editor) {
+    design = editor.design;
+    setTag("editormap", editor.map);
+    var problem = document.getElementById("problem");
+    problem.hidden = !editor.problem;
+    problem.textContent = editor.problem ? "Cannot be played yet: " + editor.problem : "";
+    document.getElementById("cavefile").value = editor.json;
+    showTunnelFrom(null);
+};
+var showTunnelFrom = function (room) {
+    tunnelFrom = room;
+    var tag = document.getElementById("tunnelfrom");
+    tag.hidden = room === null;
+    tag.textContent = "Tunnel from room " + room + ": click on the room it leads to";
+};
+// change a room clicked on in the editor, as the tool chosen says
+var editRoom = function (room) {
+    var tool = document.querySelector("input[name=tool]:checked").value;
+    if (tool != "tunnel") {
+        showTunnelFrom(null);
+        if (tool == "start") invoke({ SetStart: room });
+        else if (tool == "remove") invoke({ RemoveRoom: room });
+        else invoke({ PlaceInRoom: [room, tool] });
+    } else if (tunnelFrom === null) {
+        showTunnelFrom(room);
+    } else if (tunnelFrom == room) {
+        showTunnelFrom(null);
+    } else {
+        var tunnel = { from: tunnelFrom, to: room, both_ways: document.getElementById("bothways").checked };
+        if (design.cave[tunnelFrom - 1].indexOf(room) >= 0) invoke({ RemoveTunnel: tunnel });
+        else invoke({ AddTunnel: tunnel });
+    }
+};
+// cave design (Wumpus II)
+var designCave = function () {
+    var lines = document.getElementById("design").value.split("\n");
+    var cave = [];
+    for (i = 0; i < lines.length; i++) {
+        var rooms = lines[i].split(/[^0-9]+/).filter(function (s) { return s.length > 0; }).map(Number);
+        if (rooms.length > 0) cave.push(rooms);
+    }
+    invoke({ DesignCave: cave });
 }
+// replay of a recorded game
+var replayTimer = null;
+var replayAtEnd = false;
+var showReplay = function (response) {
+    var replay = response.replay;
+    setTag("replayturn", "turn " + replay.turn + " of " + replay.turns + ": " + replay.frame.label);
+    setTag("replaymap", response.map);
+    replayAtEnd = replay.turn >= replay.turns;
+    if (replayAtEnd) pauseReplay();
+};
+var pauseReplay = function () {
+    if (replayTimer) clearInterval(replayTimer);
+    replayTimer = null;
+    var s = document.getElementById("replayplay");
+    if (s) s.value = "Play";
+};
+var togglePlay = function () {
+    if (replayTimer) {
+        pauseReplay();
+        return;
+    }
+    if (replayAtEnd) invoke("Replay");
+    replayTimer = setInterval(function () { invoke("ReplayNext"); }, 1000);
+    document.getElementById("replayplay").value = "Pause";
+};
 console.log("compile ok");
 /* This Source Code Form is subject to the terms of the Mozilla Public
  * License, v. 2.0. If a copy of the MPL was not distributed with this
@@ -0,0 +1,220 @@
+/*! automated accessibility checks on the page rendered from the templates, and on the maps in the responses */
+
+use anyhow::Result;
+use engine::{Action, Config, Engine};
+use narthex_engine_trait::{ConfigTrait, EngineTrait, InterfaceType};
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashSet;
+
+/// the initial page for each interface, in the classic game and in Wumpus II (which has more controls)
+fn pages() -> Result<Vec<Html>> {
+    let mut pages = vec![];
+    for interface_type in &[InterfaceType::PC, InterfaceType::Android] {
+        for config in &["{}", r#"{"variant": "WumpusII"}"#] {
+            let mut engine = Engine::new(&Config::from_json(config)?, *interface_type)?;
+            pages.push(Html::parse_document(&engine.initial_html()?));
+        }
+    }
+    Ok(pages)
+}
+/// the maps sent to the webview: in the game, while aiming an arrow, in a replay (once the game is over) and in the cave editor
+fn maps() -> Result<Vec<Html>> {
+    let mut engine = Engine::new(&Config::from_json(r#"{"arrows": 1}"#)?, InterfaceType::PC)?;
+    let mut maps = vec![];
+    let room = engine.execute(Action::Instructions)?.tunnels()[0];
+    for (action, pointer) in &[
+        (Action::Instructions, "/map"),
+        (Action::Aim(vec![]), "/aim/map"),
+        // with only one arrow, the game is over once it is shot
+        (Action::Shoot(vec![room]), "/map"),
+        (Action::Replay, "/map"),
+        (Action::EditCave, "/editor/map"),
+    ] {
+        let response = serde_json::to_value(engine.execute(action.clone())?)?;
+        let map = response
+            .pointer(pointer)
+            .and_then(|map| map.as_str())
+            .unwrap_or_default();
+        assert!(!map.is_empty(), "no map after {:?}", action);
+        maps.push(Html::parse_fragment(map));
+    }
+    Ok(maps)
+}
+fn select(selector: &str) -> Selector {
+    Selector::parse(selector).expect("selector")
+}
+/// whether a form control has a name for screen readers: from a label for it, a label round it, or an ARIA attribute
+fn is_labelled(page: &Html, control: ElementRef) -> bool {
+    let element = control.value();
+    if element.attr("aria-label").is_some() || element.attr("aria-labelledby").is_some() {
+        return true;
+    }
+    let label_for = element.id().is_some_and(|id| {
+        page.select(&select("label[for]"))
+            .any(|label| label.value().attr("for") == Some(id))
+    });
+    label_for
+        || control.ancestors().any(|node| {
+            node.value()
+                .as_element()
+                .is_some_and(|ancestor| ancestor.name() == "label")
+        })
+}
+/// the relative luminance of a colour written as #RRGGBB
+fn luminance(colour: &str) -> f64 {
+    let channel = |i: usize| {
+        let value = u8::from_str_radix(&colour[i..i + 2], 16).expect("hex colour") as f64 / 255.0;
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(1) + 0.7152 * channel(3) + 0.0722 * channel(5)
+}
+/// the contrast ratio between two colours, as defined by WCAG
+fn contrast(foreground: &str, background: &str) -> f64 {
+    let (a, b) = (luminance(foreground), luminance(background));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+#[test]
+fn page_has_language_title_and_can_be_zoomed() -> Result<()> {
+    for page in pages()? {
+        let html = page.select(&select("html")).next().expect("html");
+        assert!(html
+            .value()
+            .attr("lang")
+            .is_some_and(|lang| !lang.is_empty()));
+        let title = page.select(&select("title")).next().expect("title");
+        assert!(!title.text().collect::<String>().trim().is_empty());
+        for meta in page.select(&select(r#"meta[name="viewport"]"#)) {
+            let content = meta.value().attr("content").unwrap_or_default();
+            assert!(
+                !content.contains("user-scalable=no") && !content.contains("maximum-scale"),
+                "the page cannot be zoomed: {}",
+                content
+            );
+        }
+        assert_eq!(page.select(&select("main")).count(), 1);
+    }
+    Ok(())
+}
+#[test]
+fn form_controls_are_labelled() -> Result<()> {
+    for page in pages()? {
+        let controls =
+            select(r#"input:not([type="button"]):not([type="hidden"]), select, textarea"#);
+        for control in page.select(&controls) {
+            assert!(
+                is_labelled(&page, control),
+                "no label for {}",
+                control.html()
+            );
+        }
+        for button in page.select(&select(r#"input[type="button"]"#)) {
+            assert!(
+                button
+                    .value()
+                    .attr("value")
+                    .is_some_and(|value| !value.trim().is_empty()),
+                "no text on {}",
+                button.html()
+            );
+        }
+    }
+    Ok(())
+}
+#[test]
+fn ids_are_unique() -> Result<()> {
+    for page in pages()? {
+        let mut ids = HashSet::new();
+        for element in page.select(&select("[id]")) {
+            let id = element.value().id().expect("id");
+            assert!(ids.insert(id.to_string()), "id {} is used twice", id);
+        }
+    }
+    Ok(())
+}
+#[test]
+fn messages_are_announced() -> Result<()> {
+    for page in pages()? {
+        let msgs = page.select(&select("#msgs")).next().expect("messages");
+        let element = msgs.value();
+        assert!(
+            element.attr("aria-live").is_some()
+                || matches!(
+                    element.attr("role"),
+                    Some("status") | Some("log") | Some("alert")
+                ),
+            "messages are not in a live region"
+        );
+    }
+    Ok(())
+}
+#[test]
+fn colours_have_enough_contrast() -> Result<()> {
+    let page = &pages()?[0];
+    let css: String = page
+        .select(&select("style"))
+        .flat_map(|style| style.text())
+        .collect();
+    let mut checked = 0;
+    for block in css.split('}') {
+        let (selector, body) = match block.rsplit_once('{') {
+            Some((before, body)) => (before.rsplit('{').next().unwrap_or(before), body),
+            None => continue,
+        };
+        let colour = |property: &str| {
+            body.split(';').find_map(|declaration| {
+                let (name, value) = declaration.split_once(':')?;
+                let value = value.trim();
+                (name.trim() == property && value.starts_with('#') && value.len() == 7)
+                    .then(|| value.to_string())
+            })
+        };
+        if let (Some(foreground), Some(background)) = (colour("color"), colour("background-color"))
+        {
+            let ratio = contrast(&foreground, &background);
+            assert!(
+                ratio >= 4.5,
+                "{} on {} for {} has a contrast of only {:.2}",
+                foreground,
+                background,
+                selector.trim(),
+                ratio
+            );
+            checked += 1;
+        }
+    }
+    assert!(checked > 0, "no colours found in the style sheet");
+    Ok(())
+}
+#[test]
+fn clickable_things_can_be_used_from_the_keyboard() -> Result<()> {
+    let native = ["input", "button", "select", "textarea", "a"];
+    for document in pages()?.iter().chain(&maps()?) {
+        for element in document.select(&select("[onclick]")) {
+            let element = element.value();
+            if native.contains(&element.name()) {
+                continue;
+            }
+            assert!(
+                element.attr("tabindex").is_some()
+                    && element.attr("role") == Some("button")
+                    && element.attr("aria-label").is_some(),
+                "{} can only be clicked on",
+                element.name()
+            );
+        }
+    }
+    for map in maps()? {
+        let svg = map.select(&select("svg")).next().expect("svg");
+        assert!(svg.value().attr("role").is_some());
+        assert!(svg.value().attr("aria-label").is_some());
+    }
+    Ok(())
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,156 @@
+/*! a stand-in client, that joins a game on a server and plays for one of the players */
+
+use crate::protocol::{receive, send, Message, Request};
+use anyhow::{anyhow, Result};
+use engine::Action;
+use rand::seq::SliceRandom;
+use serde_json::Value;
+use std::{
+    io::BufReader,
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/** A `Client` sits in one player's seat in a game on a server. It reads the responses as plain JSON, as a user interface would. */
+#[derive(Debug)]
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    /// the client's player number, from 1
+    player: usize,
+    /// the number of players in the game
+    players: usize,
+}
+impl Client {
+    /// connect to the server at `address` and take a seat: `player`'s (to reconnect), or any free one
+    pub fn connect(address: impl ToSocketAddrs, player: Option<usize>) -> Result<Self> {
+        let writer = TcpStream::connect(address)?;
+        let mut client = Self {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+            player: 0,
+            players: 0,
+        };
+        send(&mut client.writer, &Request::Join { player })?;
+        match client.receive()? {
+            Message::Welcome { player, players } => {
+                client.player = player;
+                client.players = players;
+                Ok(client)
+            }
+            Message::Error { message } => Err(anyhow!(message)),
+            message => Err(anyhow!("expected a welcome, not {:?}", message)),
+        }
+    }
+    /// the client's player number, from 1
+    pub fn player(&self) -> usize {
+        self.player
+    }
+    /// the number of players in the game
+    pub fn players(&self) -> usize {
+        self.players
+    }
+    /// how long to wait for a message before giving up (for ever if `None`)
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        self.writer.set_read_timeout(timeout)?;
+        Ok(())
+    }
+    /// ask the server to carry out `action`
+    pub fn act(&mut self, action: Action) -> Result<()> {
+        act(&mut self.writer, self.player, action)
+    }
+    /// something to send requests with from another thread, while this one waits for messages
+    pub fn sender(&self) -> Result<Sender> {
+        Ok(Sender {
+            writer: self.writer.try_clone()?,
+            player: self.player,
+        })
+    }
+    /// wait for the next message from the server
+    pub fn receive(&mut self) -> Result<Message<Value>> {
+        receive(&mut self.reader)?.ok_or_else(|| anyhow!("the server has gone"))
+    }
+    /// wait for the next response, skipping any other messages
+    pub fn next_response(&mut self) -> Result<Value> {
+        loop {
+            match self.receive()? {
+                Message::Response { response, .. } => return Ok(response),
+                Message::Error { message } => return Err(anyhow!(message)),
+                _ => {}
+            }
+        }
+    }
+    /// whether `response` shows that it is this client's turn
+    pub fn is_my_turn(&self, response: &Value) -> bool {
+        response["player"] == self.player && response["status"] == "Playing"
+    }
+}
+/** A `Spectator` watches a game on a server, seeing everything but taking no part. */
+#[derive(Debug)]
+pub struct Spectator {
+    reader: BufReader<TcpStream>,
+    stream: TcpStream,
+}
+impl Spectator {
+    /// connect to the server at `address` and start watching
+    pub fn connect(address: impl ToSocketAddrs) -> Result<Self> {
+        let mut stream = TcpStream::connect(address)?;
+        send(&mut stream, &Request::Watch)?;
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            stream,
+        })
+    }
+    /// how long to wait for a message before giving up (for ever if `None`)
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<()> {
+        self.stream.set_read_timeout(timeout)?;
+        Ok(())
+    }
+    /// wait for the next message from the server
+    pub fn receive(&mut self) -> Result<Message<Value>> {
+        receive(&mut self.reader)?.ok_or_else(|| anyhow!("the server has gone"))
+    }
+    /// wait for the next view of the whole game, skipping any other messages
+    pub fn next_view(&mut self) -> Result<Value> {
+        loop {
+            match self.receive()? {
+                Message::Spectate { view } => return Ok(view),
+                Message::Error { message } => return Err(anyhow!(message)),
+                _ => {}
+            }
+        }
+    }
+}
+/** A `Sender` sends requests for a [Client]. */
+#[derive(Debug)]
+pub struct Sender {
+    writer: TcpStream,
+    player: usize,
+}
+impl Sender {
+    /// ask the server to carry out `action`
+    pub fn act(&mut self, action: Action) -> Result<()> {
+        act(&mut self.writer, self.player, action)
+    }
+}
+/// ask the server to carry out `action` for `player`
+fn act(writer: &mut TcpStream, player: usize, action: Action) -> Result<()> {
+    send(writer, &Request::Act { player, action })
+}
+/// choose what to do from a response: shoot into a neighbouring room at random if a wumpus can be smelt, and otherwise wander
+pub fn choose(response: &Value) -> Action {
+    let tunnels: Vec<u8> = serde_json::from_value(response["tunnels"].clone()).unwrap_or_default();
+    let room = match tunnels.choose(&mut rand::thread_rng()) {
+        Some(room) => *room,
+        None => return Action::Pass,
+    };
+    let smelt = response["senses"]["wumpus"].as_u64().unwrap_or(0) > 0;
+    if smelt && response["arrows"].as_i64().unwrap_or(0) > 0 {
+        Action::Shoot(vec![room])
+    } else {
+        Action::Move(room)
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
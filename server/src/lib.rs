@@ -0,0 +1,17 @@
+/*! networked play: a server that owns one shared cave, and clients that play in it.
+
+The server runs a hot-seat game ([engine::Config::players]) and takes the place of the shared screen. Each client sits in one of the player's seats, or watches as a spectator. Messages go both ways as lines of JSON: clients send [Request]s and the server sends [Message]s. After each turn the player whose turn it now is gets the whole [engine::Response], and everyone else gets only what every player may see ([engine::Response::public]). Spectators get everything, including where the wumpuses and hazards are ([engine::SpectatorView]).
+
+A player who disconnects keeps their seat and can take it again by asking for it when they reconnect. A player who takes too long over their turn (perhaps because they are disconnected) lets it go by. */
+
+mod client;
+mod protocol;
+mod server;
+mod world;
+
+pub use client::{choose, Client, Sender, Spectator};
+pub use protocol::{Message, Request};
+pub use server::Server;
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
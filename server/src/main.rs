@@ -0,0 +1,185 @@
+/*! main program for networked play: runs the server, or a stand-in client */
+
+use anyhow::Result;
+use engine::Action;
+use log::debug;
+use narthex_engine_trait::ConfigTrait;
+use serde_json::Value;
+use server::{choose, Client, Message, Server, Spectator};
+use simplelog::{LevelFilter, SimpleLogger};
+use std::{
+    fs,
+    io::{self, BufRead},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+use structopt::StructOpt;
+
+/** main program */
+fn main() {
+    match main_inner() {
+        Ok(()) => {}
+        Err(err) => {
+            eprintln!("error {:?}", err)
+        }
+    }
+}
+/// command line options
+#[derive(StructOpt, Debug)]
+#[structopt(name = "server")]
+struct Options {
+    /// Address to listen on, or to connect to
+    #[structopt(short, long, default_value = "127.0.0.1:7878")]
+    address: String,
+    /// Whether to show extra debug trace
+    #[structopt(short, long)]
+    verbose: bool,
+    #[structopt(subcommand)]
+    command: Command,
+}
+/// what to do
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Run the server
+    Serve {
+        /// Config file (JSON), if not a game for two players
+        #[structopt(short, long, parse(from_os_str))]
+        config: Option<PathBuf>,
+        /// Seconds a player has to take their turn
+        #[structopt(short, long, default_value = "60")]
+        timeout: u64,
+    },
+    /// Join a game as a player, reading actions (as JSON) from standard input
+    Client {
+        /// The player to play as (to reconnect), if not the first free seat
+        #[structopt(short, long)]
+        player: Option<usize>,
+        /// Play automatically instead of reading actions
+        #[structopt(short, long)]
+        bot: bool,
+    },
+    /// Watch a game as a spectator, seeing where everything is
+    Watch,
+}
+/// actually run everything
+fn main_inner() -> Result<()> {
+    let opt = Options::from_args();
+    if opt.verbose {
+        SimpleLogger::init(LevelFilter::Trace, simplelog::Config::default())?;
+        debug!("options: {:?}", opt);
+    }
+    match &opt.command {
+        Command::Serve { config, timeout } => {
+            let config = match config {
+                Some(path) => engine::Config::from_json(&fs::read_to_string(path)?)?,
+                None => {
+                    let mut config = engine::Config::default();
+                    config.set_players(2);
+                    config
+                }
+            };
+            let server = Server::bind(&opt.address, &config, Duration::from_secs(*timeout))?;
+            println!("serving on {}", server.local_addr()?);
+            server.run()
+        }
+        Command::Client { player, bot } => {
+            let client = Client::connect(&opt.address, *player)?;
+            println!(
+                "playing as player {} of {}",
+                client.player(),
+                client.players()
+            );
+            if *bot {
+                play(client)
+            } else {
+                type_in(client)
+            }
+        }
+        Command::Watch => {
+            let mut spectator = Spectator::connect(&opt.address)?;
+            loop {
+                show(&spectator.next_view()?);
+            }
+        }
+    }
+}
+/// show everything about the game, as text
+fn show(view: &Value) {
+    let rooms = |rooms: &Value| {
+        rooms
+            .as_array()
+            .map(|rooms| {
+                rooms
+                    .iter()
+                    .map(Value::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default()
+    };
+    println!("TURN {}: PLAYER {}'S TURN", view["turn"], view["player"]);
+    for player in view["players"].as_array().into_iter().flatten() {
+        println!(
+            "PLAYER {} IN ROOM {} WITH {} ARROWS{}",
+            player["number"],
+            player["room"],
+            player["arrows"],
+            if player["out"].is_null() {
+                ""
+            } else {
+                " (OUT)"
+            }
+        );
+    }
+    println!("WUMPUSES: {}", rooms(&view["wumpuses"]));
+    for hazard in view["hazards"].as_array().into_iter().flatten() {
+        println!(
+            "{}: {}",
+            hazard["label"].as_str().unwrap_or_default(),
+            rooms(&hazard["rooms"])
+        );
+    }
+    println!(
+        "{}\n",
+        view["msgs"]
+            .as_str()
+            .unwrap_or_default()
+            .replace("<br/>", "\n")
+    );
+}
+/// play automatically
+fn play(mut client: Client) -> Result<()> {
+    loop {
+        let response = client.next_response()?;
+        println!("{}", response["msgs"]);
+        if client.is_my_turn(&response) {
+            thread::sleep(Duration::from_millis(500));
+            let action = choose(&response);
+            println!("=> {:?}", action);
+            client.act(action)?;
+        }
+    }
+}
+/// send the actions typed in, and show what comes back
+fn type_in(mut client: Client) -> Result<()> {
+    let mut sender = client.sender()?;
+    thread::spawn(move || loop {
+        match client.receive() {
+            Ok(Message::Response { response, .. }) => println!("{}", response["msgs"]),
+            Ok(message) => println!("{:?}", message),
+            Err(err) => {
+                eprintln!("{}", err);
+                break;
+            }
+        }
+    });
+    for line in io::stdin().lock().lines() {
+        let action: Action = serde_json::from_str(&line?)?;
+        sender.act(action)?;
+    }
+    Ok(())
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,52 @@
+/*! the messages that go between the server and its clients, one JSON object to a line */
+
+use anyhow::{anyhow, Result};
+use engine::Action;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+/** A `Request` is sent by a client to the server. */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Request {
+    /// take a seat: a particular player's (to reconnect), or any free one
+    Join { player: Option<usize> },
+    /// do something, as `player` (which must be the client's own seat)
+    Act { player: usize, action: Action },
+    /// watch the game as a spectator, seeing everything but taking no part
+    Watch,
+}
+/** A `Message` is sent by the server to a client. The server sends [engine::Response]s to players and [engine::SpectatorView]s to spectators; a client can read them as any types that they deserialize to. */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Message<R, S = R> {
+    /// the client has the seat of `player`, in a game for `players` players
+    Welcome { player: usize, players: usize },
+    /// what `player` (the client) sees now
+    Response { player: usize, response: R },
+    /// everything there is to see now, for a spectator
+    Spectate { view: S },
+    /// a player has connected or disconnected
+    Connection { player: usize, connected: bool },
+    /// the client's request could not be carried out
+    Error { message: String },
+}
+/// write `message` as a line of JSON
+pub fn send<T: Serialize>(writer: &mut impl Write, message: &T) -> Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+/// read a line of JSON, or `None` if the other end has closed the connection
+pub fn receive<T: DeserializeOwned>(reader: &mut impl BufRead) -> Result<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|err| anyhow!("bad message {:?}: {}", line.trim_end(), err))
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
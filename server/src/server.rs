@@ -0,0 +1,123 @@
+/*! the server: accepts clients and passes their requests on to the shared game */
+
+use crate::{
+    protocol::{receive, send, Message, Request},
+    world::World,
+};
+use anyhow::Result;
+use engine::{Config, Response};
+use log::{debug, error, warn};
+use std::{
+    io::BufReader,
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/** A `Server` owns the shared game and listens for clients. */
+#[derive(Debug)]
+pub struct Server {
+    listener: TcpListener,
+    world: Arc<Mutex<World>>,
+    /// how long a player has to take their turn
+    timeout: Duration,
+}
+impl Server {
+    /// set up a game with `config`, listening on `address`
+    pub fn bind(address: impl ToSocketAddrs, config: &Config, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(address)?,
+            world: Arc::new(Mutex::new(World::new(config)?)),
+            timeout,
+        })
+    }
+    /// the address the server is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+    /// serve clients until the program stops
+    pub fn run(self) -> Result<()> {
+        let world = self.world.clone();
+        let timeout = self.timeout;
+        thread::spawn(move || loop {
+            thread::sleep((timeout / 10).max(Duration::from_millis(10)));
+            lock(&world).check_time(timeout);
+        });
+        for stream in self.listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let world = self.world.clone();
+                    thread::spawn(move || {
+                        if let Err(err) = serve(&world, stream) {
+                            warn!("client failed: {}", err);
+                        }
+                    });
+                }
+                Err(err) => error!("could not accept a client: {}", err),
+            }
+        }
+        Ok(())
+    }
+}
+/// get at the shared game, even if another thread panicked while using it
+fn lock(world: &Mutex<World>) -> std::sync::MutexGuard<'_, World> {
+    world
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+/// handle the requests from one client until they disconnect
+fn serve(world: &Mutex<World>, stream: TcpStream) -> Result<()> {
+    debug!("client connected from {}", stream.peer_addr()?);
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let player = match receive(&mut reader)? {
+        Some(Request::Join { player }) => lock(world).join(player, stream)?,
+        Some(Request::Watch) => return watch(world, stream, reader),
+        Some(Request::Act { .. }) => {
+            let message = "JOIN THE GAME FIRST".to_string();
+            return send(&mut &stream, &Message::<Response>::Error { message });
+        }
+        None => return Ok(()),
+    };
+    loop {
+        match receive(&mut reader) {
+            Ok(Some(Request::Act {
+                player: acting,
+                action,
+            })) if acting == player => lock(world).act(player, action),
+            Ok(Some(_)) => lock(world).refuse(player, format!("YOU ARE PLAYER {}", player)),
+            Ok(None) => break,
+            Err(err) => {
+                warn!("player {}: {}", player, err);
+                if err.is::<std::io::Error>() {
+                    break;
+                }
+                lock(world).refuse(player, err.to_string());
+            }
+        }
+    }
+    lock(world).leave(player);
+    Ok(())
+}
+/// send everything about the game to a spectator until they disconnect
+fn watch(world: &Mutex<World>, stream: TcpStream, mut reader: BufReader<TcpStream>) -> Result<()> {
+    let number = lock(world).watch(stream);
+    loop {
+        match receive::<Request>(&mut reader) {
+            Ok(Some(_)) => lock(world).refuse_spectator(number),
+            Ok(None) => break,
+            Err(err) => {
+                warn!("spectator {}: {}", number, err);
+                if err.is::<std::io::Error>() {
+                    break;
+                }
+                lock(world).refuse_spectator(number);
+            }
+        }
+    }
+    lock(world).unwatch(number);
+    Ok(())
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,258 @@
+/*! the shared game, and the seats of the players in it */
+
+use crate::protocol::{send, Message};
+use anyhow::{anyhow, Result};
+use engine::{Action, Config, Engine, Response, SpectatorView, Status};
+use log::{debug, warn};
+use narthex_engine_trait::{EngineTrait, InterfaceType};
+use std::{
+    collections::HashMap,
+    net::TcpStream,
+    sync::mpsc::{self, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// what the server sends to its clients
+type Outgoing = Message<Response, SpectatorView>;
+
+/** A `Postman` sends the messages for one client on a thread of its own, in the order they were posted, so that nothing is sent while the world is locked and a slow client holds up nobody else. */
+#[derive(Debug)]
+struct Postman {
+    sender: Sender<Outgoing>,
+}
+impl Postman {
+    /// start sending to `stream`; `name` says who is there, for the log
+    fn new(mut stream: TcpStream, name: String) -> Self {
+        let (sender, receiver) = mpsc::channel::<Outgoing>();
+        thread::spawn(move || {
+            for message in receiver {
+                if let Err(err) = send(&mut stream, &message) {
+                    warn!("could not send to {}: {}", name, err);
+                    break;
+                }
+            }
+        });
+        Self { sender }
+    }
+    /// send `message` once those posted before it have gone
+    fn post(&self, message: Outgoing) {
+        // the postman only stops once the client has gone
+        let _ = self.sender.send(message);
+    }
+}
+/** A `Seat` is a player's place in the game. */
+#[derive(Debug, Default)]
+struct Seat {
+    /// the connection to the client sitting here, if there is one
+    postman: Option<Postman>,
+    /// whether a client has ever sat here
+    taken: bool,
+}
+/** The `World` is the game that all the players share. */
+#[derive(Debug)]
+pub struct World {
+    engine: Engine,
+    /// one for each player, in order
+    seats: Vec<Seat>,
+    /// the latest response, from the view of the player whose turn it is
+    last: Response,
+    /// when the current turn started, once every seat has been taken
+    turn_started: Option<Instant>,
+    /// the connections to the spectators, by number
+    spectators: HashMap<usize, Postman>,
+    /// the number to give the next spectator
+    next_spectator: usize,
+}
+impl World {
+    /// create the game
+    pub fn new(config: &Config) -> Result<Self> {
+        let mut engine = Engine::new(config, InterfaceType::PC)?;
+        let last = engine.execute(Action::ShowKnowledge(!config.purist()))?;
+        Ok(Self {
+            engine,
+            seats: (0..config.players()).map(|_| Seat::default()).collect(),
+            last,
+            turn_started: None,
+            spectators: HashMap::new(),
+            next_spectator: 1,
+        })
+    }
+    /// seat a newly connected client, returning their player number
+    pub fn join(&mut self, wanted: Option<usize>, stream: TcpStream) -> Result<usize> {
+        let free = |seat: &Seat| seat.postman.is_none();
+        let index = match wanted {
+            Some(player) => player
+                .checked_sub(1)
+                .filter(|i| self.seats.get(*i).is_some_and(free)),
+            // a player coming back must ask for their seat, so no one else can take over their game
+            None => self.seats.iter().position(|seat| !seat.taken),
+        };
+        let index = match index {
+            Some(index) => index,
+            None => {
+                let message = match wanted {
+                    Some(player) => format!("THE SEAT OF PLAYER {} IS NOT FREE", player),
+                    None => "THERE ARE NO FREE SEATS".to_string(),
+                };
+                Postman::new(stream, "a client with no seat".to_string()).post(Outgoing::Error {
+                    message: message.clone(),
+                });
+                return Err(anyhow!(message));
+            }
+        };
+        let player = index + 1;
+        debug!("player {} joined", player);
+        let postman = Postman::new(stream, format!("player {}", player));
+        postman.post(Outgoing::Welcome {
+            player,
+            players: self.seats.len(),
+        });
+        self.seats[index] = Seat {
+            postman: Some(postman),
+            taken: true,
+        };
+        let view = self.view(player, &self.last.clone());
+        self.tell(player, &view);
+        self.tell_others(
+            player,
+            &Message::Connection {
+                player,
+                connected: true,
+            },
+        );
+        if self.turn_started.is_none() && self.seats.iter().all(|seat| seat.taken) {
+            self.turn_started = Some(Instant::now());
+        }
+        Ok(player)
+    }
+    /// start sending everything about the game to a new spectator, returning their number
+    pub fn watch(&mut self, stream: TcpStream) -> usize {
+        let number = self.next_spectator;
+        self.next_spectator += 1;
+        debug!("spectator {} is watching", number);
+        let postman = Postman::new(stream, format!("spectator {}", number));
+        postman.post(Outgoing::Spectate {
+            view: self.engine.spectate(),
+        });
+        self.spectators.insert(number, postman);
+        number
+    }
+    /// spectator `number` has gone
+    pub fn unwatch(&mut self, number: usize) {
+        debug!("spectator {} has gone", number);
+        self.spectators.remove(&number);
+    }
+    /// tell spectator `number` that they cannot do anything
+    pub fn refuse_spectator(&mut self, number: usize) {
+        if let Some(postman) = self.spectators.get(&number) {
+            let message = "SPECTATORS CANNOT TAKE PART".to_string();
+            postman.post(Outgoing::Error { message });
+        }
+    }
+    /// the client sitting in `player`'s seat has gone
+    pub fn leave(&mut self, player: usize) {
+        debug!("player {} left", player);
+        self.seats[player - 1].postman = None;
+        self.tell_others(
+            player,
+            &Message::Connection {
+                player,
+                connected: false,
+            },
+        );
+    }
+    /// carry out `action` for `player`, and tell everyone what they can see of the result
+    pub fn act(&mut self, player: usize, action: Action) {
+        if let Err(err) = self.try_act(player, action) {
+            self.refuse(player, err.to_string());
+        }
+    }
+    /// tell `player` that their request could not be carried out
+    pub fn refuse(&mut self, player: usize, message: String) {
+        self.tell(player, &Message::Error { message });
+    }
+    fn try_act(&mut self, player: usize, action: Action) -> Result<()> {
+        let over = self.last.status() != Status::Playing;
+        match action {
+            Action::ReStart if over => {}
+            Action::ReStart => return Err(anyhow!("THE GAME IS NOT OVER YET")),
+            _ if self.last.player() != player => {
+                return Err(anyhow!("IT IS PLAYER {}'S TURN", self.last.player()))
+            }
+            Action::Move(_)
+            | Action::Shoot(_)
+            | Action::Pass
+            | Action::Hint
+            | Action::Instructions
+            | Action::ShowKnowledge(_) => {}
+            Action::Aim(_) => return self.aim(player, action),
+            _ => return Err(anyhow!("YOU CANNOT DO THAT IN A NETWORK GAME")),
+        }
+        let response = self.engine.execute(action)?;
+        if response.player() != self.last.player() || response.turn() != self.last.turn() {
+            self.turn_started = self.turn_started.map(|_| Instant::now());
+        }
+        for number in 1..=self.seats.len() {
+            let view = self.view(number, &response);
+            self.tell(number, &view);
+        }
+        self.last = response;
+        self.tell_spectators(&Message::Spectate {
+            view: self.engine.spectate(),
+        });
+        Ok(())
+    }
+    /// show `player` alone where the arrow they are aiming can go, as aiming changes nothing in the game
+    fn aim(&mut self, player: usize, action: Action) -> Result<()> {
+        let response = self.engine.execute(action)?;
+        let view = self.view(player, &response);
+        self.tell(player, &view);
+        Ok(())
+    }
+    /// let the turn go by if the player whose turn it is has taken too long
+    pub fn check_time(&mut self, timeout: Duration) {
+        let late = self
+            .turn_started
+            .is_some_and(|started| started.elapsed() >= timeout);
+        if late && self.last.status() == Status::Playing {
+            let player = self.last.player();
+            debug!("player {} has run out of time", player);
+            self.act(player, Action::Pass);
+        }
+    }
+    /// what `player` can see of `response`
+    fn view(&self, player: usize, response: &Response) -> Outgoing {
+        Message::Response {
+            player,
+            response: if player == response.player() {
+                response.clone()
+            } else {
+                response.public()
+            },
+        }
+    }
+    /// send a message to `player`, if they are connected
+    fn tell(&mut self, player: usize, message: &Outgoing) {
+        if let Some(postman) = &self.seats[player - 1].postman {
+            postman.post(message.clone());
+        }
+    }
+    /// send a message to everyone except `player`
+    fn tell_others(&mut self, player: usize, message: &Outgoing) {
+        for number in 1..=self.seats.len() {
+            if number != player {
+                self.tell(number, message);
+            }
+        }
+    }
+    /// send a message to every spectator
+    fn tell_spectators(&mut self, message: &Outgoing) {
+        for postman in self.spectators.values() {
+            postman.post(message.clone());
+        }
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,205 @@
+/*! a networked game between clients on this machine, including disconnecting, reconnecting and running out of time */
+
+use anyhow::Result;
+use engine::{Action, Config};
+use serde_json::Value;
+use server::{Client, Message, Server, Spectator};
+use std::{net::SocketAddr, thread, time::Duration};
+
+/// how long a player has to take their turn
+const TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// start a server for two players on a free local port
+fn start() -> Result<SocketAddr> {
+    start_with(Config::default())
+}
+/// start a server for two players on a free local port, with the rest of `config`
+fn start_with(mut config: Config) -> Result<SocketAddr> {
+    config.set_players(2).set_seed(Some(1));
+    let server = Server::bind("127.0.0.1:0", &config, TIMEOUT)?;
+    let address = server.local_addr()?;
+    thread::spawn(move || server.run());
+    Ok(address)
+}
+/// join the game, giving up on messages that take too long
+fn join(address: SocketAddr, player: Option<usize>) -> Result<Client> {
+    let mut client = Client::connect(address, player)?;
+    client.set_timeout(Some(5 * TIMEOUT))?;
+    Ok(client)
+}
+/// whether a response is only what every player may see
+fn is_public(response: &Value) -> bool {
+    response["tunnels"].as_array().is_none_or(Vec::is_empty)
+}
+#[test]
+fn networked_game() -> Result<()> {
+    let address = start()?;
+    let mut first = join(address, None)?;
+    assert_eq!(first.player(), 1);
+    assert_eq!(first.players(), 2);
+    let response = first.next_response()?;
+    assert!(first.is_my_turn(&response));
+    assert!(!is_public(&response));
+
+    let mut second = join(address, None)?;
+    assert_eq!(second.player(), 2);
+    let response = second.next_response()?;
+    assert!(!second.is_my_turn(&response));
+    assert!(is_public(&response));
+
+    // only the player whose turn it is may act
+    second.act(Action::Pass)?;
+    match second.receive()? {
+        Message::Error { message } => assert_eq!(message, "IT IS PLAYER 1'S TURN"),
+        message => panic!("expected an error, not {:?}", message),
+    }
+
+    // after the first player's turn, the second player sees everything and the first only what is public
+    first.act(Action::Pass)?;
+    let response = second.next_response()?;
+    assert!(second.is_my_turn(&response));
+    assert!(!is_public(&response));
+    let response = first.next_response()?;
+    assert!(is_public(&response));
+    assert_eq!(
+        response["msgs"],
+        "PLAYER 1 LETS THE TURN GO BY<br/>PLAYER 2'S TURN"
+    );
+
+    // the second player disconnects, and everyone else is told
+    drop(second);
+    match first.receive()? {
+        Message::Connection { player, connected } => assert_eq!((player, connected), (2, false)),
+        message => panic!("expected a disconnection, not {:?}", message),
+    }
+
+    // the second player runs out of time, so it is the first player's turn again
+    let response = first.next_response()?;
+    assert!(first.is_my_turn(&response));
+    assert!(response["msgs"]
+        .as_str()
+        .unwrap_or_default()
+        .starts_with("PLAYER 2 LETS THE TURN GO BY"));
+
+    // the second player gets their seat back, and there are no more seats
+    let mut second = join(address, Some(2))?;
+    assert_eq!(second.player(), 2);
+    assert!(is_public(&second.next_response()?));
+    assert!(join(address, None).is_err());
+
+    first.act(Action::Pass)?;
+    let response = second.next_response()?;
+    assert!(second.is_my_turn(&response));
+    Ok(())
+}
+#[test]
+fn only_the_player_gets_their_seat_back() -> Result<()> {
+    let address = start()?;
+    let mut first = join(address, None)?;
+    let second = join(address, None)?;
+    first.next_response()?;
+
+    // a newcomer may not take the seat of a player who has gone
+    drop(second);
+    while !matches!(
+        first.receive()?,
+        Message::Connection {
+            player: 2,
+            connected: false
+        }
+    ) {}
+    assert!(join(address, None).is_err());
+    let second = join(address, Some(2))?;
+    assert_eq!(second.player(), 2);
+    Ok(())
+}
+#[test]
+fn spectator_sees_everything() -> Result<()> {
+    let address = start()?;
+    let mut spectator = Spectator::connect(address)?;
+    spectator.set_timeout(Some(5 * TIMEOUT))?;
+    let view = spectator.next_view()?;
+    assert_eq!(view["wumpuses"].as_array().map(Vec::len), Some(1));
+    assert_eq!(view["players"].as_array().map(Vec::len), Some(2));
+    assert!(view["hazards"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .all(|hazard| hazard["rooms"]
+            .as_array()
+            .is_some_and(|rooms| !rooms.is_empty())));
+
+    let mut first = join(address, None)?;
+    let mut second = join(address, None)?;
+    first.next_response()?;
+    second.next_response()?;
+
+    // the spectator sees the whole of each turn
+    first.act(Action::Pass)?;
+    let view = spectator.next_view()?;
+    assert_eq!(view["player"], 2);
+    assert_eq!(view["turn"], 1);
+    let player = &view["players"][1];
+    assert_eq!(player["current"], true);
+    assert!(view["msgs"]
+        .as_str()
+        .unwrap_or_default()
+        .starts_with("PLAYER 1 LETS THE TURN GO BY"));
+
+    // players see only their own view, with no hidden positions
+    let response = second.next_response()?;
+    assert!(response.get("hazards").is_none());
+    assert!(response["wumpuses"].is_u64());
+    Ok(())
+}
+#[test]
+fn knowledge_is_shown_unless_purist() -> Result<()> {
+    for purist in [false, true] {
+        let mut config = Config::default();
+        config.set_purist(purist);
+        let address = start_with(config)?;
+        let mut first = join(address, None)?;
+        let response = first.next_response()?;
+        let knowledge = response["knowledge"].as_array().map(Vec::len);
+        assert_eq!(knowledge == Some(0), purist, "purist {}", purist);
+    }
+    Ok(())
+}
+#[test]
+fn player_can_aim_an_arrow() -> Result<()> {
+    let address = start()?;
+    let mut first = join(address, None)?;
+    let mut second = join(address, None)?;
+    let tunnels = first.next_response()?["tunnels"].clone();
+    second.next_response()?;
+
+    // only the player whose turn it is may aim, and only they see where the arrow can go
+    second.act(Action::Aim(vec![]))?;
+    match second.receive()? {
+        Message::Error { message } => assert_eq!(message, "IT IS PLAYER 1'S TURN"),
+        message => panic!("expected an error, not {:?}", message),
+    }
+    first.act(Action::Aim(vec![]))?;
+    let response = first.next_response()?;
+    assert_eq!(response["aim"]["path"], Value::Array(vec![]));
+    let mut next = response["aim"]["next"].as_array().cloned().unwrap_or_default();
+    let mut expected = tunnels.as_array().cloned().unwrap_or_default();
+    let number = |room: &Value| room.as_u64();
+    next.sort_by_key(number);
+    expected.sort_by_key(number);
+    assert_eq!(next, expected);
+
+    // aiming uses no turn, and the other player is not told about it
+    first.act(Action::Pass)?;
+    let response = second.next_response()?;
+    assert!(second.is_my_turn(&response));
+    assert!(response["msgs"]
+        .as_str()
+        .unwrap_or_default()
+        .starts_with("PLAYER 1 LETS THE TURN GO BY"));
+    assert_eq!(response["turn"], 1);
+    Ok(())
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,47 @@
+/*! an agent that follows the hints */
+
+use crate::{explore::Explorer, Agent};
+use engine::{Action, Advice, Chances, Response};
+
+/** A `BayesAgent` asks for a hint every turn. It shoots when the hint says to, and otherwise heads for the unexplored room least likely to hold a hazard. */
+#[derive(Debug, Default)]
+pub struct BayesAgent {
+    explorer: Explorer,
+}
+impl Agent for BayesAgent {
+    fn reset(&mut self, _seed: u64) {
+        self.explorer.clear();
+    }
+    fn choose(&mut self, response: &Response) -> Action {
+        let hint = match response.hint() {
+            None => return Action::Hint,
+            Some(hint) => hint,
+        };
+        let here = response.room();
+        self.explorer.visit(here, response.tunnels());
+        let chances = |room: u8| hint.rooms()[room as usize - 1];
+        match hint.advice() {
+            Advice::Shoot { path, .. } if response.arrows() > 0 => Action::Shoot(path.clone()),
+            Advice::Confused => Action::Move(response.tunnels()[0]),
+            advice => {
+                let advised = match advice {
+                    Advice::Move { room, .. } => *room,
+                    _ => response.tunnels()[0],
+                };
+                let step = self.explorer.next_step(
+                    here,
+                    |room| danger(&chances(room)) == 0.0,
+                    |room| danger(&chances(room)),
+                );
+                Action::Move(step.map_or(advised, |(room, _)| room))
+            }
+        }
+    }
+}
+/// how likely going into a room is to end badly (a little for bats, as they may move the player somewhere worse)
+fn danger(chances: &Chances) -> f64 {
+    chances.wumpus + chances.pit + 0.1 * chances.bats
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,108 @@
+/*! estimating how hard a game is, by playing it many times with each strategy */
+
+use crate::{run, Strategy, Summary};
+use anyhow::Result;
+use engine::Config;
+use std::fmt;
+
+/** An `Estimate` is how hard a game is for each strategy, and overall. The difficulty is the chance of not winning: 0 is a certain win, 1 a certain loss. */
+#[derive(Debug, Clone)]
+pub struct Estimate {
+    pub summaries: Vec<(Strategy, Summary)>,
+}
+impl Estimate {
+    /// the difficulty averaged over the strategies
+    pub fn difficulty(&self) -> f64 {
+        self.summaries
+            .iter()
+            .map(|(_, summary)| summary.difficulty())
+            .sum::<f64>()
+            / self.summaries.len().max(1) as f64
+    }
+    /// the variance of [Estimate::difficulty]
+    pub fn variance(&self) -> f64 {
+        self.summaries
+            .iter()
+            .map(|(_, summary)| summary.variance())
+            .sum::<f64>()
+            / (self.summaries.len().max(1) as f64).powi(2)
+    }
+}
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:8} {:>6} {:>10} {:>10} {:>10} {:>8}",
+            "strategy", "games", "difficulty", "variance", "std error", "turns"
+        )?;
+        for (strategy, summary) in &self.summaries {
+            writeln!(
+                f,
+                "{:8} {:>6} {:>10.3} {:>10.6} {:>10.3} {:>8.1}",
+                strategy.to_string(),
+                summary.games,
+                summary.difficulty(),
+                summary.variance(),
+                summary.variance().sqrt(),
+                summary.average_turns()
+            )?;
+        }
+        writeln!(
+            f,
+            "{:8} {:>6} {:>10.3} {:>10.6} {:>10.3}",
+            "overall",
+            "",
+            self.difficulty(),
+            self.variance(),
+            self.variance().sqrt()
+        )
+    }
+}
+/// play `games` games with each of `strategies`, using the same seeds for each
+pub fn estimate(
+    config: &Config,
+    strategies: &[Strategy],
+    games: usize,
+    seed: u64,
+    max_actions: usize,
+) -> Result<Estimate> {
+    let mut summaries = vec![];
+    for strategy in strategies {
+        summaries.push((*strategy, run(config, *strategy, games, seed, max_actions)?));
+    }
+    Ok(Estimate { summaries })
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use narthex_engine_trait::ConfigTrait;
+
+    #[test]
+    fn harder_settings_are_estimated_harder() -> Result<()> {
+        let easy = Config::from_json(r#"{"pits": 0, "bats": 0, "arrows": 10}"#)?;
+        let hard = Config::from_json(r#"{"pits": 4, "bats": 4, "arrows": 2}"#)?;
+        let strategies = [Strategy::Random, Strategy::Rules];
+        let easy = estimate(&easy, &strategies, 40, 1, 500)?;
+        let hard = estimate(&hard, &strategies, 40, 1, 500)?;
+        assert!(
+            easy.difficulty() < hard.difficulty(),
+            "easy {} hard {}",
+            easy.difficulty(),
+            hard.difficulty()
+        );
+        assert_eq!(easy.summaries.len(), 2);
+        assert!(easy.variance() > 0.0);
+        Ok(())
+    }
+    #[test]
+    fn same_seed_gives_the_same_estimate() -> Result<()> {
+        let config = Config::default();
+        let first = estimate(&config, &Strategy::ALL, 5, 7, 500)?;
+        let again = estimate(&config, &Strategy::ALL, 5, 7, 500)?;
+        assert_eq!(first.to_string(), again.to_string());
+        Ok(())
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
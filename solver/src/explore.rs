@@ -0,0 +1,51 @@
+/*! finding the way round the parts of the cave the agent has seen */
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/** An `Explorer` remembers the tunnels out of each room the agent has been in, and finds routes to rooms not yet explored. */
+#[derive(Debug, Default)]
+pub struct Explorer {
+    tunnels: HashMap<u8, Vec<u8>>,
+}
+impl Explorer {
+    /// forget everything (for a new game)
+    pub fn clear(&mut self) {
+        self.tunnels.clear();
+    }
+    /// note the tunnels out of a room
+    pub fn visit(&mut self, room: u8, tunnels: &[u8]) {
+        self.tunnels.insert(room, tunnels.to_vec());
+    }
+    /** the room to move to next, heading for the unexplored room with the least `danger` that can be reached only through rooms that are `safe`, nearest first. Returns the first step and the danger of the room it leads to. */
+    pub fn next_step<D: PartialOrd + Copy>(
+        &self,
+        here: u8,
+        safe: impl Fn(u8) -> bool,
+        danger: impl Fn(u8) -> D,
+    ) -> Option<(u8, D)> {
+        let mut seen = HashSet::new();
+        seen.insert(here);
+        let mut queue: VecDeque<(u8, u8)> =
+            self.tunnels[&here].iter().map(|to| (*to, *to)).collect();
+        let mut best: Option<(u8, D)> = None;
+        while let Some((room, first)) = queue.pop_front() {
+            if !seen.insert(room) {
+                continue;
+            }
+            match self.tunnels.get(&room) {
+                Some(tunnels) if safe(room) => queue.extend(tunnels.iter().map(|to| (*to, first))),
+                Some(_) => {}
+                None => {
+                    let d = danger(room);
+                    if best.is_none_or(|(_, b)| d < b) {
+                        best = Some((first, d));
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
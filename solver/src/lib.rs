@@ -0,0 +1,250 @@
+/*! automatic players for the wumpus game, used to measure how hard a game is.
+
+An [Agent] plays through [EngineTrait::execute] and sees only what a human player would see: the [Response]. */
+
+mod bayes;
+mod estimate;
+mod explore;
+mod random;
+mod rules;
+
+use anyhow::Result;
+use engine::{Action, Cause, Config, Engine, Response, Status};
+use log::debug;
+use narthex_engine_trait::{EngineTrait, InterfaceType};
+use std::{collections::HashMap, fmt};
+use strum::{Display, EnumString};
+
+pub use bayes::BayesAgent;
+pub use estimate::{estimate, Estimate};
+pub use random::RandomAgent;
+pub use rules::RulesAgent;
+
+/** An `Agent` chooses what to do next from what the game shows it. */
+pub trait Agent {
+    /// get ready to play a new game
+    fn reset(&mut self, seed: u64);
+    /// choose the next action. [Action::Hint] may be chosen as well as moving or shooting, as it does not use up a turn.
+    fn choose(&mut self, response: &Response) -> Action;
+}
+/** A `Strategy` is a kind of [Agent]. */
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Strategy {
+    /// wander about and shoot when the wumpus is smelt
+    Random,
+    /// keep to rooms known to be safe, and shoot when sure of the wumpus
+    Rules,
+    /// follow the hints
+    Bayes,
+}
+impl Strategy {
+    /// all the strategies
+    pub const ALL: [Strategy; 3] = [Self::Random, Self::Rules, Self::Bayes];
+    /// create an [Agent] for this strategy
+    pub fn agent(&self) -> Box<dyn Agent> {
+        match self {
+            Self::Random => Box::new(RandomAgent::default()),
+            Self::Rules => Box::new(RulesAgent::default()),
+            Self::Bayes => Box::new(BayesAgent::default()),
+        }
+    }
+}
+/** An `Outcome` is how one game ended. */
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome {
+    /// how the game ended ([Status::Playing] if it was abandoned)
+    pub status: Status,
+    /// the number of turns taken
+    pub turns: u32,
+}
+/// play one game with `agent`, giving up after `max_actions` actions
+pub fn play(
+    config: &Config,
+    agent: &mut dyn Agent,
+    seed: u64,
+    max_actions: usize,
+) -> Result<Outcome> {
+    let mut config = config.clone();
+    config.set_seed(Some(seed)).set_purist(false);
+    let mut engine = Engine::new(&config, InterfaceType::PC)?;
+    agent.reset(seed);
+    let mut response = engine.execute(Action::ShowKnowledge(true))?;
+    for _ in 0..max_actions {
+        if response.status() != Status::Playing {
+            break;
+        }
+        let action = agent.choose(&response);
+        debug!("agent chose {:?}", &action);
+        response = engine.execute(action)?;
+    }
+    Ok(Outcome {
+        status: response.status(),
+        turns: response.turn(),
+    })
+}
+/// try to solve puzzle `number` of pack `pack` (as the game offers them) with `agent`, giving up after `max_actions` actions; returns the moves and shots that solved it, if they did
+pub fn solve(
+    config: &Config,
+    agent: &mut dyn Agent,
+    pack: usize,
+    number: usize,
+    max_actions: usize,
+) -> Result<Option<Vec<Action>>> {
+    let mut config = config.clone();
+    config.set_purist(false);
+    let mut engine = Engine::new(&config, InterfaceType::PC)?;
+    agent.reset(0);
+    let mut response = engine.execute(Action::StartPuzzle(pack, number))?;
+    let mut solution = vec![];
+    for _ in 0..max_actions {
+        if response.status() != Status::Playing {
+            break;
+        }
+        let action = agent.choose(&response);
+        debug!("agent chose {:?}", &action);
+        if matches!(action, Action::Move(_) | Action::Shoot(_) | Action::Pass) {
+            solution.push(action.clone());
+        }
+        response = engine.execute(action)?;
+    }
+    Ok(if response.status() == Status::Won {
+        Some(solution)
+    } else {
+        None
+    })
+}
+/** A `Summary` adds up the outcomes of many games. */
+#[derive(Debug, Default, Clone)]
+pub struct Summary {
+    pub games: usize,
+    pub wins: usize,
+    /// games abandoned because they went on too long
+    pub unfinished: usize,
+    /// the total number of turns over all games
+    pub turns: u64,
+    pub deaths: HashMap<Cause, usize>,
+}
+impl Summary {
+    /// add the outcome of a game
+    pub fn add(&mut self, outcome: &Outcome) {
+        self.games += 1;
+        self.turns += outcome.turns as u64;
+        match outcome.status {
+            Status::Playing => self.unfinished += 1,
+            Status::Won => self.wins += 1,
+            Status::Lost(cause) => *self.deaths.entry(cause).or_default() += 1,
+        }
+    }
+    /// the proportion of games won
+    pub fn win_rate(&self) -> f64 {
+        self.wins as f64 / self.games.max(1) as f64
+    }
+    /// the average number of turns per game
+    pub fn average_turns(&self) -> f64 {
+        self.turns as f64 / self.games.max(1) as f64
+    }
+    /// the proportion of games not won (abandoned games count as lost)
+    pub fn difficulty(&self) -> f64 {
+        1.0 - self.win_rate()
+    }
+    /// the variance of [Summary::difficulty] as an estimate of the chance of not winning
+    pub fn variance(&self) -> f64 {
+        let p = self.difficulty();
+        p * (1.0 - p) / self.games.max(1) as f64
+    }
+}
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |n: usize| 100.0 * n as f64 / self.games.max(1) as f64;
+        writeln!(f, "games:         {}", self.games)?;
+        writeln!(
+            f,
+            "won:           {} ({:.1}%)",
+            self.wins,
+            100.0 * self.win_rate()
+        )?;
+        writeln!(f, "average turns: {:.1}", self.average_turns())?;
+        let mut deaths: Vec<_> = self.deaths.iter().collect();
+        deaths.sort_by(|a, b| b.1.cmp(a.1).then(a.0.to_string().cmp(&b.0.to_string())));
+        for (cause, count) in deaths {
+            writeln!(f, "lost ({:11}) {} ({:.1}%)", cause, count, percent(*count))?;
+        }
+        if self.unfinished > 0 {
+            writeln!(
+                f,
+                "unfinished:    {} ({:.1}%)",
+                self.unfinished,
+                percent(self.unfinished)
+            )?;
+        }
+        Ok(())
+    }
+}
+/// play `games` games with seeds starting at `seed`
+pub fn run(
+    config: &Config,
+    strategy: Strategy,
+    games: usize,
+    seed: u64,
+    max_actions: usize,
+) -> Result<Summary> {
+    let mut agent = strategy.agent();
+    let mut summary = Summary::default();
+    for game in 0..games as u64 {
+        let outcome = play(config, agent.as_mut(), seed + game, max_actions)?;
+        debug!("game {}: {:?}", game, &outcome);
+        summary.add(&outcome);
+    }
+    Ok(summary)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_strategy_finishes_its_games() -> Result<()> {
+        let config = Config::default();
+        for strategy in &Strategy::ALL {
+            let summary = run(&config, *strategy, 20, 1, 500)?;
+            assert_eq!(summary.games, 20);
+            assert_eq!(summary.unfinished, 0, "{} left games unfinished", strategy);
+            assert_eq!(summary.wins + summary.deaths.values().sum::<usize>(), 20);
+            assert!(summary.turns > 0);
+            // the same seeds give the same games
+            assert_eq!(
+                run(&config, *strategy, 20, 1, 500)?.wins,
+                summary.wins,
+                "{}",
+                strategy
+            );
+        }
+        Ok(())
+    }
+    #[test]
+    fn summary_adds_up_the_outcomes() {
+        let mut summary = Summary::default();
+        for status in &[
+            Status::Won,
+            Status::Lost(Cause::Pit),
+            Status::Lost(Cause::Pit),
+            Status::Playing,
+        ] {
+            summary.add(&Outcome {
+                status: *status,
+                turns: 5,
+            });
+        }
+        assert_eq!(summary.games, 4);
+        assert_eq!(summary.wins, 1);
+        assert_eq!(summary.unfinished, 1);
+        assert_eq!(summary.deaths[&Cause::Pit], 2);
+        assert_eq!(summary.win_rate(), 0.25);
+        assert_eq!(summary.difficulty(), 0.75);
+        assert_eq!(summary.average_turns(), 5.0);
+        assert!((summary.variance() - 0.75 * 0.25 / 4.0).abs() < 1e-12);
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
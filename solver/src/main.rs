@@ -0,0 +1,113 @@
+/*! main program for the solver: plays many games automatically and reports how they went */
+
+use anyhow::Result;
+use log::debug;
+use narthex_engine_trait::{ConfigTrait, EngineTrait, InterfaceType};
+use simplelog::{LevelFilter, SimpleLogger};
+use solver::{estimate, run, solve, Strategy};
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+
+/** main program */
+fn main() {
+    match main_inner() {
+        Ok(()) => {}
+        Err(err) => {
+            eprintln!("error {:?}", err)
+        }
+    }
+}
+/// command line options
+#[derive(StructOpt, Debug)]
+#[structopt(name = "solver")]
+struct Options {
+    /// Config file (JSON), if not the standard game
+    #[structopt(short, long, parse(from_os_str))]
+    config: Option<PathBuf>,
+    /// Number of games to play (with each strategy)
+    #[structopt(short, long, default_value = "1000")]
+    games: usize,
+    /// Seed for the first game (each game uses the next seed)
+    #[structopt(long, default_value = "1")]
+    seed: u64,
+    /// Give up on a game after this many actions
+    #[structopt(long, default_value = "1000")]
+    max_actions: usize,
+    /// Whether to show extra debug trace
+    #[structopt(short, long)]
+    verbose: bool,
+    #[structopt(subcommand)]
+    command: Command,
+}
+/// what to do
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Play games with one strategy and report how they went
+    Play {
+        /// How to play: random, rules or bayes
+        #[structopt(short, long, default_value = "rules")]
+        strategy: Strategy,
+    },
+    /// Estimate how hard the game is, by playing it with several strategies
+    Estimate {
+        /// The strategies to use (all of them if none are given)
+        #[structopt(short, long)]
+        strategy: Vec<Strategy>,
+    },
+    /// Try to solve each puzzle with each strategy
+    Puzzles,
+}
+/// actually run everything
+fn main_inner() -> Result<()> {
+    let opt = Options::from_args();
+    if opt.verbose {
+        SimpleLogger::init(LevelFilter::Trace, simplelog::Config::default())?;
+        debug!("options: {:?}", opt);
+    }
+    let config = match &opt.config {
+        Some(path) => engine::Config::from_json(&fs::read_to_string(path)?)?,
+        None => engine::Config::default(),
+    };
+    match &opt.command {
+        Command::Play { strategy } => {
+            let summary = run(&config, *strategy, opt.games, opt.seed, opt.max_actions)?;
+            println!("strategy:      {}", strategy);
+            print!("{}", summary);
+        }
+        Command::Estimate { strategy } => {
+            let strategies = if strategy.is_empty() {
+                &Strategy::ALL[..]
+            } else {
+                &strategy[..]
+            };
+            let estimate = estimate(&config, strategies, opt.games, opt.seed, opt.max_actions)?;
+            print!("{}", estimate);
+        }
+        Command::Puzzles => {
+            let engine = engine::Engine::new(&config, InterfaceType::PC)?;
+            for (pack_number, pack) in engine.puzzle_packs().iter().enumerate() {
+                for (number, puzzle) in pack.puzzles.iter().enumerate() {
+                    print!("{} - {}:", pack.name, puzzle.name);
+                    for strategy in &Strategy::ALL {
+                        let mut agent = strategy.agent();
+                        match solve(
+                            &config,
+                            agent.as_mut(),
+                            pack_number,
+                            number,
+                            opt.max_actions,
+                        )? {
+                            Some(solution) => print!(" {} in {}", strategy, solution.len()),
+                            None => print!(" {} failed", strategy),
+                        }
+                    }
+                    println!();
+                }
+            }
+        }
+    }
+    Ok(())
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
@@ -0,0 +1,37 @@
+/*! an agent that wanders about at random */
+
+use crate::Agent;
+use engine::{Action, Response};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+/** A `RandomAgent` moves at random, and shoots into a random room when it smells the wumpus. */
+#[derive(Debug)]
+pub struct RandomAgent {
+    rng: StdRng,
+}
+impl Default for RandomAgent {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+}
+impl Agent for RandomAgent {
+    fn reset(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+    fn choose(&mut self, response: &Response) -> Action {
+        let room = *response
+            .tunnels()
+            .choose(&mut self.rng)
+            .unwrap_or(&response.room());
+        if response.senses().wumpus > 0 && self.rng.gen_bool(0.5) {
+            Action::Shoot(vec![room])
+        } else {
+            Action::Move(room)
+        }
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
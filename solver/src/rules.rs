@@ -0,0 +1,111 @@
+/*! an agent that follows simple rules of thumb */
+
+use crate::{explore::Explorer, Agent};
+use engine::{Action, Response, RoomKnowledge, Suspicion};
+
+/** A `RulesAgent` shoots the wumpus when it knows where it is, and otherwise explores through rooms known to be safe, taking a chance only when it has to. */
+#[derive(Debug, Default)]
+pub struct RulesAgent {
+    explorer: Explorer,
+}
+impl Agent for RulesAgent {
+    fn reset(&mut self, _seed: u64) {
+        self.explorer.clear();
+    }
+    fn choose(&mut self, response: &Response) -> Action {
+        let here = response.room();
+        let next = response.tunnels();
+        self.explorer.visit(here, next);
+        let knowledge = response.knowledge();
+        let known = |room: u8| &knowledge[room as usize - 1];
+        if response.arrows() > 0 {
+            if let Some(room) = next
+                .iter()
+                .find(|room| known(**room).wumpus == Suspicion::Certain)
+            {
+                return Action::Shoot(vec![*room]);
+            }
+        }
+        let step = self.explorer.next_step(
+            here,
+            |room| {
+                let room = known(room);
+                room.pit == Suspicion::No
+                    && room.bats == Suspicion::No
+                    && room.wumpus != Suspicion::Certain
+            },
+            |room| risk(known(room)),
+        );
+        let chancy = step.is_none_or(|(_, risk)| risk > 0);
+        if chancy && response.senses().wumpus > 0 && response.arrows() > 1 {
+            if let Some(room) = next
+                .iter()
+                .find(|room| known(**room).wumpus == Suspicion::Possible)
+            {
+                return Action::Shoot(vec![*room]);
+            }
+        }
+        let room = step.map(|(room, _)| room).unwrap_or_else(|| {
+            next.iter()
+                .cloned()
+                .min_by_key(|room| risk(known(*room)))
+                .unwrap_or(here)
+        });
+        Action::Move(room)
+    }
+}
+/// how risky it is to go into a room (lower is better)
+fn risk(room: &RoomKnowledge) -> u32 {
+    let weight = |suspicion: Suspicion| match suspicion {
+        Suspicion::No => 0,
+        Suspicion::Unknown => 1,
+        Suspicion::Possible => 2,
+        Suspicion::Certain => 100,
+    };
+    10 * (weight(room.pit) + weight(room.wumpus)) + weight(room.bats)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use engine::{Config, Engine, Status};
+    use narthex_engine_trait::{EngineTrait, InterfaceType};
+
+    #[test]
+    fn never_walks_into_a_known_pit_or_wumpus() -> Result<()> {
+        let mut agent = RulesAgent::default();
+        for seed in 0..30 {
+            let mut config = Config::default();
+            config.set_seed(Some(seed)).set_purist(false);
+            let mut engine = Engine::new(&config, InterfaceType::PC)?;
+            agent.reset(seed);
+            let mut response = engine.execute(Action::ShowKnowledge(true))?;
+            while response.status() == Status::Playing {
+                let action = agent.choose(&response);
+                // bats may be risked when every other way on is worse, as they only carry the player off
+                if let Action::Move(room) = action {
+                    let known = &response.knowledge()[room as usize - 1];
+                    assert_ne!(
+                        known.pit,
+                        Suspicion::Certain,
+                        "game {}: pit in {}",
+                        seed,
+                        room
+                    );
+                    assert_ne!(
+                        known.wumpus,
+                        Suspicion::Certain,
+                        "game {}: wumpus in {}",
+                        seed,
+                        room
+                    );
+                }
+                response = engine.execute(action)?;
+            }
+        }
+        Ok(())
+    }
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
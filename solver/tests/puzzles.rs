@@ -0,0 +1,34 @@
+/*! every puzzle that comes with the game can be solved */
+
+use anyhow::Result;
+use engine::{Config, Engine};
+use narthex_engine_trait::{EngineTrait, InterfaceType};
+use solver::{solve, Strategy};
+
+#[test]
+fn shipped_puzzles_are_solvable() -> Result<()> {
+    let config = Config::default();
+    let engine = Engine::new(&config, InterfaceType::PC)?;
+    assert!(!engine.puzzle_packs().is_empty());
+    for (pack_number, pack) in engine.puzzle_packs().iter().enumerate() {
+        for (number, puzzle) in pack.puzzles.iter().enumerate() {
+            let mut solution = None;
+            for strategy in &Strategy::ALL {
+                let mut agent = strategy.agent();
+                solution = solve(&config, agent.as_mut(), pack_number, number, 100)?;
+                if solution.is_some() {
+                    break;
+                }
+            }
+            let solution = solution
+                .unwrap_or_else(|| panic!("no strategy solves {} - {}", pack.name, puzzle.name));
+            assert!(solution.len() <= puzzle.moves as usize);
+            assert!(Engine::solves(puzzle, &solution)?, "{}", puzzle.name);
+            assert!(!Engine::solves(puzzle, &[])?, "{}", puzzle.name);
+        }
+    }
+    Ok(())
+}
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/. */
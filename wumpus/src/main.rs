@@ -5,6 +5,7 @@ use log::debug;
 use narthex_engine_trait::{EngineTrait, InterfaceType};
 use narthex_web_app::{UserData, WebParams};
 use simplelog::{LevelFilter, SimpleLogger};
+use std::{env, path::Path};
 use structopt::StructOpt;
 
 /** main program */
@@ -50,7 +51,14 @@ fn run_engine_with_webview(opt: &Options) -> Result<()> {
     }
     //let config_text = fs::read_to_string(&opt.config)?;
     //  let config: engine::Config = toml::from_str(&config_text)?;
-    let config = engine::Config::default();
+    let mut config = engine::Config::default();
+    if let Some(home) = env::var_os("HOME") {
+        let home = Path::new(&home);
+        config
+            .set_high_scores_file(Some(home.join(".wumpus-scores.json")))
+            .set_stats_file(Some(home.join(".wumpus-stats.json")))
+            .set_cave_file(Some(home.join(".wumpus-cave.json")));
+    }
     let engine = engine::Engine::new(&config, InterfaceType::PC)?;
     let user_data = UserData::new(engine);
     let params = WebParams {